@@ -0,0 +1,447 @@
+use crate::config::Config;
+use crate::date_expr::{self, DateRange};
+use crate::diary_entry::DiaryEntry;
+use crate::diary_state::DiaryState;
+use crate::drafts::Drafts;
+use crate::keymap::Keymap;
+use crate::prompts::Prompts;
+use crate::screens::{MainScreen, RecoverDraftsScreen};
+use crate::stats;
+use crate::templates::Template;
+use crate::text_area::KillRing;
+use chrono::{DateTime, Local, NaiveDate};
+use color_eyre::Result;
+use crossterm::{
+    cursor::SetCursorStyle,
+    event::{self, Event, KeyEvent},
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    ExecutableCommand,
+};
+use ratatui::{
+    backend::Backend,
+    layout::{Alignment, Rect},
+    style::{Color, Style},
+    widgets::Paragraph,
+    Frame, Terminal,
+};
+use std::io::stdout;
+use std::time::{Duration, Instant};
+
+/// How often screens get an [`AppEvent::Tick`] when no key is pressed.
+const TICK_RATE: Duration = Duration::from_millis(250);
+/// How long a notification stays up.
+const NOTIFICATION_DURATION: Duration = Duration::from_secs(3);
+
+pub enum AppEvent {
+    Key(KeyEvent),
+    /// Time passing, for autosaving, expiring messages and the like.
+    Tick,
+}
+
+/// What a screen hands back to the one below it when it closes.
+pub enum Outcome {
+    /// The answer to a yes/no question.
+    Confirmed(bool),
+    /// Text submitted in a prompt.
+    Input(String),
+    /// The entry the viewer was showing when it was closed.
+    Viewed(usize),
+}
+
+/// What the app should do after a screen handled an event.
+pub enum Transition {
+    Stay,
+    Push(Box<dyn Screen>),
+    /// Closes the screen and opens another in its place.
+    Replace(Box<dyn Screen>),
+    /// Closes the screen, passing the outcome to the one below.
+    Pop(Option<Outcome>),
+    /// Gives the terminal back to the user for [`Screen::run_suspended`].
+    Suspend,
+    Quit,
+}
+
+/// The cursor shape a screen wants, for vim's modes.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CursorShape {
+    Default,
+    Block,
+    Bar,
+}
+
+/// One screen on the app's stack. Only the top screen gets events; it is
+/// drawn over the screens below it if it is an overlay.
+pub trait Screen {
+    fn render(&mut self, f: &mut Frame, ctx: &Context);
+
+    fn handle_event(&mut self, event: AppEvent, ctx: &mut Context) -> Transition;
+
+    /// Called when the screen above this one closes.
+    fn resume(&mut self, _outcome: Option<Outcome>, _ctx: &mut Context) -> Transition {
+        Transition::Stay
+    }
+
+    /// Whether the screens below stay visible around this one.
+    fn is_overlay(&self) -> bool {
+        false
+    }
+
+    fn cursor_shape(&self) -> CursorShape {
+        CursorShape::Default
+    }
+
+    /// Runs with the terminal out of raw mode, after the screen returned
+    /// [`Transition::Suspend`].
+    fn run_suspended(&mut self, _ctx: &mut Context) {}
+}
+
+/// State shared by all the screens.
+pub struct Context {
+    pub diary: DiaryState,
+    pub config: Config,
+    pub drafts: Drafts,
+    pub prompts: Prompts,
+    pub templates: Vec<Template>,
+    pub keymap: Keymap,
+    pub kill_ring: KillRing,
+    pub date_filter: Option<String>,
+    /// Whether the viewer shows entries as typed rather than rendered.
+    pub raw_view: bool,
+    /// Whether the entry editor shows a rendered preview beside the text.
+    pub preview: bool,
+    notification: Option<(String, Instant)>,
+    /// The time to take as now instead of the clock's, for tests.
+    fixed_now: Option<DateTime<Local>>,
+}
+
+impl Context {
+    pub fn new(
+        diary: DiaryState,
+        config: Config,
+        drafts: Drafts,
+        prompts: Prompts,
+        templates: Vec<Template>,
+    ) -> Self {
+        Context {
+            diary,
+            keymap: Keymap::new(config.editing_mode),
+            config,
+            drafts,
+            prompts,
+            templates,
+            kill_ring: KillRing::default(),
+            date_filter: None,
+            raw_view: false,
+            preview: false,
+            notification: None,
+            fixed_now: None,
+        }
+    }
+
+    /// Makes the app take `now` as the current time, so that what it shows
+    /// for today doesn't depend on the day the tests run.
+    #[cfg(test)]
+    pub fn at(self, now: DateTime<Local>) -> Self {
+        Context {
+            fixed_now: Some(now),
+            ..self
+        }
+    }
+
+    pub fn now(&self) -> DateTime<Local> {
+        self.fixed_now.unwrap_or_else(Local::now)
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.now().date_naive()
+    }
+
+    /// Shows `message` at the bottom of the screen for a few seconds.
+    pub fn notify(&mut self, message: impl Into<String>) {
+        self.notification = Some((message.into(), Instant::now()));
+    }
+
+    /// The date filter and the range it currently resolves to. The expression
+    /// is kept rather than the range so that `today` follows the clock.
+    pub fn date_filter_range(&self) -> Option<(&str, DateRange)> {
+        let expression = self.date_filter.as_deref()?;
+        let range = date_expr::parse(expression, self.today()).ok()?;
+        Some((expression, range))
+    }
+
+    /// Entries shown in the entry lists, narrowed by the date filter if set.
+    pub fn visible_entries(&self) -> Vec<&DiaryEntry> {
+        let range = self.date_filter_range().map(|(_, range)| range);
+        self.diary
+            .get_entries()
+            .iter()
+            .filter(|e| range.is_none_or(|r| r.contains(e.timestamp.date_naive())))
+            .collect()
+    }
+}
+
+/// Where the app's events come from, together with the parts of the terminal
+/// that ratatui's backends don't cover. Tests script their own.
+pub trait EventSource {
+    /// Waits for the next event. `None` ends the app.
+    fn next_event(&mut self) -> Result<Option<AppEvent>>;
+
+    fn set_cursor_shape(&mut self, _shape: CursorShape) -> Result<()> {
+        Ok(())
+    }
+
+    /// Gives the terminal back to the user while `f` runs, for programs that
+    /// take over the terminal.
+    fn suspend(&mut self, f: &mut dyn FnMut()) -> Result<()> {
+        f();
+        Ok(())
+    }
+}
+
+/// The real terminal: keys from crossterm, in raw mode on the alternate
+/// screen until dropped.
+pub struct CrosstermEvents;
+
+impl CrosstermEvents {
+    pub fn new() -> Result<Self> {
+        enable_raw_mode()?;
+        stdout().execute(EnterAlternateScreen)?;
+        Ok(CrosstermEvents)
+    }
+}
+
+impl EventSource for CrosstermEvents {
+    fn next_event(&mut self) -> Result<Option<AppEvent>> {
+        if !event::poll(TICK_RATE)? {
+            return Ok(Some(AppEvent::Tick));
+        }
+        Ok(Some(match event::read()? {
+            Event::Key(key) => AppEvent::Key(key),
+            // Anything else, such as a resize, just needs a redraw.
+            _ => AppEvent::Tick,
+        }))
+    }
+
+    fn set_cursor_shape(&mut self, shape: CursorShape) -> Result<()> {
+        stdout().execute(match shape {
+            CursorShape::Default => SetCursorStyle::DefaultUserShape,
+            CursorShape::Block => SetCursorStyle::SteadyBlock,
+            CursorShape::Bar => SetCursorStyle::BlinkingBar,
+        })?;
+        Ok(())
+    }
+
+    fn suspend(&mut self, f: &mut dyn FnMut()) -> Result<()> {
+        disable_raw_mode()?;
+        stdout().execute(LeaveAlternateScreen)?;
+        f();
+        enable_raw_mode()?;
+        stdout().execute(EnterAlternateScreen)?;
+        Ok(())
+    }
+}
+
+impl Drop for CrosstermEvents {
+    fn drop(&mut self) {
+        disable_raw_mode().unwrap();
+        stdout().execute(LeaveAlternateScreen).unwrap();
+    }
+}
+
+pub struct App<B: Backend> {
+    terminal: Terminal<B>,
+    events: Box<dyn EventSource>,
+    ctx: Context,
+    stack: Vec<Box<dyn Screen>>,
+    cursor_shape: CursorShape,
+}
+
+impl<B: Backend> App<B> {
+    /// Opens the main screen, first offering to recover any drafts left from
+    /// the last run.
+    pub fn new(terminal: Terminal<B>, events: Box<dyn EventSource>, mut ctx: Context) -> Self {
+        let mut stack: Vec<Box<dyn Screen>> = vec![Box::new(MainScreen::default())];
+        if let Some(recover) = RecoverDraftsScreen::new(&mut ctx) {
+            stack.push(Box::new(recover));
+        }
+        // A notice from startup, such as about files that couldn't be read,
+        // matters more than the reminder.
+        if ctx.notification.is_none() {
+            if let Some(reminder) = goal_reminder(&ctx) {
+                ctx.notify(reminder);
+            }
+        }
+        App {
+            terminal,
+            events,
+            ctx,
+            stack,
+            cursor_shape: CursorShape::Default,
+        }
+    }
+
+    /// Draws the screens and feeds them events until the last one closes or
+    /// the events run out.
+    pub fn run(&mut self) -> Result<()> {
+        while !self.stack.is_empty() {
+            self.draw()?;
+
+            let Some(event) = self.events.next_event()? else {
+                break;
+            };
+            if matches!(event, AppEvent::Tick) {
+                self.expire_notification();
+            }
+
+            let Some(screen) = self.stack.last_mut() else {
+                break;
+            };
+            let transition = screen.handle_event(event, &mut self.ctx);
+            self.apply(transition)?;
+        }
+        if self.cursor_shape != CursorShape::Default {
+            self.events.set_cursor_shape(CursorShape::Default)?;
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self) -> Result<()> {
+        let stack = &mut self.stack;
+        let ctx = &self.ctx;
+        // Draw from the topmost full screen up, so overlays sit on top of it.
+        let first = stack.iter().rposition(|s| !s.is_overlay()).unwrap_or(0);
+        self.terminal.draw(|f| {
+            for screen in &mut stack[first..] {
+                screen.render(f, ctx);
+            }
+            render_notification(f, ctx);
+        })?;
+
+        let shape = self
+            .stack
+            .last()
+            .map_or(CursorShape::Default, |s| s.cursor_shape());
+        if shape != self.cursor_shape {
+            self.events.set_cursor_shape(shape)?;
+            self.cursor_shape = shape;
+        }
+        Ok(())
+    }
+
+    fn apply(&mut self, mut transition: Transition) -> Result<()> {
+        loop {
+            transition = match transition {
+                Transition::Stay => return Ok(()),
+                Transition::Push(screen) => {
+                    self.stack.push(screen);
+                    return Ok(());
+                }
+                Transition::Replace(screen) => {
+                    self.stack.pop();
+                    self.stack.push(screen);
+                    return Ok(());
+                }
+                Transition::Pop(outcome) => {
+                    self.stack.pop();
+                    match self.stack.last_mut() {
+                        Some(screen) => screen.resume(outcome, &mut self.ctx),
+                        None => return Ok(()),
+                    }
+                }
+                Transition::Suspend => {
+                    self.suspended()?;
+                    return Ok(());
+                }
+                Transition::Quit => {
+                    self.stack.clear();
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Runs the top screen's [`Screen::run_suspended`] with the terminal back
+    /// in its normal state.
+    fn suspended(&mut self) -> Result<()> {
+        self.events.set_cursor_shape(CursorShape::Default)?;
+        self.cursor_shape = CursorShape::Default;
+        let (stack, ctx) = (&mut self.stack, &mut self.ctx);
+        self.events.suspend(&mut || {
+            if let Some(screen) = stack.last_mut() {
+                screen.run_suspended(ctx);
+            }
+        })?;
+        self.terminal.clear()?;
+        Ok(())
+    }
+
+    fn expire_notification(&mut self) {
+        if let Some((_, shown_at)) = &self.ctx.notification {
+            if shown_at.elapsed() >= NOTIFICATION_DURATION {
+                self.ctx.notification = None;
+            }
+        }
+    }
+}
+
+/// A nudge towards a writing goal that is set and not yet met. The day's
+/// word goal comes first, being the nearer one; the week's entries are only
+/// mentioned once it is met, so the reminder stays one short line.
+fn goal_reminder(ctx: &Context) -> Option<String> {
+    let goals = &ctx.config.goals;
+    let entries = ctx.diary.get_entries();
+    let today = ctx.today();
+
+    let words = stats::words_on(entries, today);
+    if let Some(goal) = goals.words_per_day.filter(|&goal| words < goal) {
+        return Some(match words {
+            0 => format!("Today's goal is {} words. Happy writing!", goal),
+            _ => format!(
+                "{} of {} words written today. Just {} to go!",
+                words,
+                goal,
+                goal - words
+            ),
+        });
+    }
+
+    let written = stats::entries_this_week(entries, today);
+    let goal = goals.entries_per_week.filter(|&goal| written < goal)?;
+    Some(match written {
+        0 => format!("This week's goal is {} entries. Happy writing!", goal),
+        _ => format!(
+            "{} of {} entries written this week. Just {} to go!",
+            written,
+            goal,
+            goal - written
+        ),
+    })
+}
+
+/// Shows the notification in the bottom margin that every screen leaves.
+fn render_notification(f: &mut Frame, ctx: &Context) {
+    let Some((message, _)) = &ctx.notification else {
+        return;
+    };
+    let area = f.area();
+    let row = Rect {
+        y: area.bottom().saturating_sub(1),
+        height: area.height.min(1),
+        ..area
+    };
+    let notification = Paragraph::new(format!(" {} ", message))
+        .style(Style::default().fg(Color::Black).bg(Color::Green))
+        .alignment(Alignment::Center);
+    let width = (message.chars().count() as u16 + 2).min(row.width);
+    f.render_widget(
+        notification,
+        Rect {
+            x: row.x + (row.width - width) / 2,
+            width,
+            ..row
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,855 @@
+//! End-to-end tests of the screens: keys are scripted against an app drawing
+//! to a [`TestBackend`], and the rendered buffer and the diary are checked.
+
+use super::*;
+use crate::config::{Config, EditingMode, Goals};
+use crate::drafts::Draft;
+use crate::prompts::Prompts;
+use crate::search::{SavedSearch, SearchMode, SearchQuery};
+use crate::templates::Template;
+use chrono::{DateTime, Datelike, TimeZone};
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::backend::TestBackend;
+use std::collections::VecDeque;
+
+const WIDTH: u16 = 80;
+const HEIGHT: u16 = 24;
+
+/// Plays back a fixed list of events, then ends the app.
+struct Script(VecDeque<AppEvent>);
+
+impl EventSource for Script {
+    fn next_event(&mut self) -> Result<Option<AppEvent>> {
+        Ok(self.0.pop_front())
+    }
+}
+
+enum Input {
+    Type(&'static str),
+    Key(KeyCode),
+    Ctrl(char),
+}
+
+use Input::*;
+
+fn entry(day: u32, content: &str, tags: &[&str]) -> DiaryEntry {
+    DiaryEntry {
+        timestamp: Local.with_ymd_and_hms(2024, 3, day, 9, 30, 0).unwrap(),
+        ..DiaryEntry::new(
+            0,
+            content.to_string(),
+            tags.iter().map(|t| t.to_string()).collect(),
+        )
+    }
+}
+
+/// The time the tests' app takes as now: a Thursday, long after the
+/// entries made by [`entry`].
+fn now() -> DateTime<Local> {
+    Local.with_ymd_and_hms(2024, 3, 14, 12, 0, 0).unwrap()
+}
+
+fn app_with(entries: Vec<DiaryEntry>, drafts: Drafts, width: u16, height: u16) -> App<TestBackend> {
+    let mut diary = DiaryState::in_memory();
+    for entry in entries {
+        diary.add_entry(entry);
+    }
+    let terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    let ctx = Context::new(
+        diary,
+        Config::default(),
+        drafts,
+        Prompts::default(),
+        Vec::new(),
+    )
+    .at(now());
+    App::new(terminal, Box::new(Script(VecDeque::new())), ctx)
+}
+
+/// An app on a diary of two entries.
+fn app() -> App<TestBackend> {
+    app_with(
+        vec![
+            entry(1, "Walked the dog\nIt rained all day.", &["pets"]),
+            entry(2, "Met Sam for lunch", &["friends", "food"]),
+        ],
+        Drafts::in_memory(),
+        WIDTH,
+        HEIGHT,
+    )
+}
+
+fn play(app: &mut App<TestBackend>, inputs: &[Input]) {
+    let key = |code, modifiers| AppEvent::Key(KeyEvent::new(code, modifiers));
+    let events = inputs
+        .iter()
+        .flat_map(|input| match input {
+            Type(text) => text
+                .chars()
+                .map(|c| key(KeyCode::Char(c), KeyModifiers::NONE))
+                .collect(),
+            Key(code) => vec![key(*code, KeyModifiers::NONE)],
+            Ctrl(c) => vec![key(KeyCode::Char(*c), KeyModifiers::CONTROL)],
+        })
+        .collect();
+    app.events = Box::new(Script(events));
+    app.run().unwrap();
+}
+
+/// The rendered screen, one line per row with trailing spaces trimmed.
+fn screen(app: &App<TestBackend>) -> String {
+    let buffer = app.terminal.backend().buffer();
+    let width = buffer.area.width as usize;
+    buffer
+        .content
+        .chunks(width)
+        .map(|row| {
+            let line: String = row.iter().map(|cell| cell.symbol()).collect();
+            line.trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn assert_shows(app: &App<TestBackend>, text: &str) {
+    let screen = screen(app);
+    assert!(
+        screen.contains(text),
+        "{:?} not on screen:\n{}",
+        text,
+        screen
+    );
+}
+
+fn assert_hides(app: &App<TestBackend>, text: &str) {
+    let screen = screen(app);
+    assert!(!screen.contains(text), "{:?} on screen:\n{}", text, screen);
+}
+
+fn contents(app: &App<TestBackend>) -> Vec<&str> {
+    let entries = app.ctx.diary.get_entries();
+    entries.iter().map(|e| e.content.as_str()).collect()
+}
+
+#[test]
+fn main_screen_lists_and_previews_the_entries() {
+    let mut app = app_with(
+        vec![
+            entry(1, "Walked the dog\nIt rained all day.", &["pets"]),
+            entry(2, "Met Sam for lunch", &["friends", "food"]),
+        ],
+        Drafts::in_memory(),
+        WIDTH,
+        16,
+    );
+    play(&mut app, &[]);
+    assert_eq!(
+        screen(&app),
+        [
+            "",
+            "                                 Personal Diary",
+            "",
+            "",
+            " ┌Entries (oldest first)───────────┐┌2024-03-01 09:30─────────────────────────┐",
+            " │> Walked the dog                 ││Walked the dog                           │",
+            " │  2024-03-01 09:30 · pets        ││It rained all day.                       │",
+            " │  Met Sam for lunch              ││                                         │",
+            " │  2024-03-02 09:30 · friends, foo││                                         │",
+            " │                                 ││                                         │",
+            " │                                 ││                                         │",
+            " └─────────────────────────────────┘└Tags: pets───────────────────────────────┘",
+            " Enter to read, w/e/d to write/edit/delete, s/f to search/filter by date, t/i/p",
+            "  for tasks/stats/prompts, c/y for the month/year, o/g to sort/group, q to quit",
+            "",
+            "",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn previews_the_selected_entry() {
+    let mut app = app();
+    play(&mut app, &[]);
+    assert_shows(&app, "> Walked the dog");
+    assert_shows(&app, "│It rained all day.");
+    assert_shows(&app, "Tags: pets");
+
+    play(&mut app, &[Key(KeyCode::Down)]);
+    assert_shows(&app, "> Met Sam for lunch");
+    assert_hides(&app, "It rained all day.");
+    assert_shows(&app, "Tags: friends, food");
+}
+
+#[test]
+fn shows_a_hand_edited_mood_as_five_stars() {
+    let mut app = app_with(
+        vec![DiaryEntry {
+            mood: Some(9),
+            ..entry(1, "Best day ever", &[])
+        }],
+        Drafts::in_memory(),
+        WIDTH,
+        HEIGHT,
+    );
+    play(&mut app, &[]);
+    assert_shows(&app, "★★★★★");
+    assert_hides(&app, "☆");
+}
+
+#[test]
+fn quits() {
+    let mut app = app();
+    play(&mut app, &[Type("q")]);
+    assert!(app.stack.is_empty());
+}
+
+#[test]
+fn writes_an_entry() {
+    let mut app = app();
+    play(
+        &mut app,
+        &[
+            Type("w"),
+            Type("Went hiking"),
+            Key(KeyCode::Enter),
+            Type("Saw a #heron"),
+        ],
+    );
+    assert_shows(&app, "New Diary Entry");
+    assert_shows(&app, "│Went hiking");
+    assert_shows(&app, "│Saw a #heron");
+
+    play(
+        &mut app,
+        &[
+            Key(KeyCode::Tab),
+            Type("outdoors, birds"),
+            Key(KeyCode::Up),
+            Ctrl('s'),
+        ],
+    );
+    assert_shows(&app, "Personal Diary");
+    assert_shows(&app, "Went hiking");
+    assert_shows(&app, "Entry saved");
+    let added = app.ctx.diary.get_entries().last().unwrap();
+    assert_eq!(added.content, "Went hiking\nSaw a #heron");
+    assert_eq!(added.tags, ["outdoors", "birds"]);
+    assert_eq!(added.mood, Some(3));
+}
+
+#[test]
+fn empty_entry_is_not_added() {
+    let mut app = app();
+    play(&mut app, &[Type("w"), Type("   "), Ctrl('s')]);
+    assert_shows(&app, "Personal Diary");
+    assert_eq!(app.ctx.diary.get_entries().len(), 2);
+}
+
+#[test]
+fn edits_an_entry() {
+    let mut app = app();
+    play(
+        &mut app,
+        &[
+            Key(KeyCode::Down),
+            Type("e"),
+            Key(KeyCode::End),
+            Type(" and Alex"),
+        ],
+    );
+    assert_shows(&app, "Edit Diary Entry");
+    assert_shows(&app, "│Met Sam for lunch and Alex");
+
+    play(&mut app, &[Ctrl('s')]);
+    assert_shows(&app, "> Met Sam for lunch and Alex");
+    assert_eq!(
+        contents(&app),
+        [
+            "Walked the dog\nIt rained all day.",
+            "Met Sam for lunch and Alex"
+        ]
+    );
+    assert_eq!(app.ctx.diary.get_entries()[1].modified, Some(now()));
+
+    // Saving without a change doesn't count as modifying the entry.
+    play(&mut app, &[Key(KeyCode::Up), Type("e"), Ctrl('s')]);
+    assert_eq!(app.ctx.diary.get_entries()[0].modified, None);
+}
+
+#[test]
+fn undoes_tag_edits_in_vim_mode() {
+    let mut app = app();
+    app.ctx.config.editing_mode = EditingMode::Vim;
+    app.ctx.keymap = Keymap::new(EditingMode::Vim);
+    play(
+        &mut app,
+        &[
+            Type("e"),
+            Key(KeyCode::Tab),
+            Key(KeyCode::End),
+            Type(", dogs"),
+        ],
+    );
+    assert_shows(&app, "Ctrl+Z/Y: Undo/Redo");
+    assert_shows(&app, "│pets, dogs");
+
+    play(&mut app, &[Ctrl('z')]);
+    assert_hides(&app, "pets, dogs");
+    play(&mut app, &[Ctrl('y')]);
+    assert_shows(&app, "│pets, dogs");
+}
+
+#[test]
+fn discarding_changes_asks_first() {
+    let mut app = app();
+    play(&mut app, &[Type("e"), Type(" Or not."), Key(KeyCode::Esc)]);
+    assert_shows(&app, "Discard the changes to this entry?");
+    // The editor stays visible around the question.
+    assert_shows(&app, "Edit Diary Entry");
+
+    play(&mut app, &[Type("n")]);
+    assert_hides(&app, "Discard the changes to this entry?");
+    assert_shows(&app, "│It rained all day. Or not.");
+
+    play(&mut app, &[Key(KeyCode::Esc), Type("y")]);
+    assert_shows(&app, "Personal Diary");
+    assert_eq!(contents(&app)[0], "Walked the dog\nIt rained all day.");
+}
+
+#[test]
+fn unchanged_entry_closes_without_asking() {
+    let mut app = app();
+    play(&mut app, &[Type("e"), Key(KeyCode::Esc)]);
+    assert_shows(&app, "Personal Diary");
+}
+
+#[test]
+fn deletes_an_entry() {
+    let mut app = app();
+    play(&mut app, &[Key(KeyCode::Down), Type("d")]);
+    assert_shows(&app, "Delete \"Met Sam for lunch\"?");
+
+    play(&mut app, &[Type("n")]);
+    assert_eq!(app.ctx.diary.get_entries().len(), 2);
+
+    play(&mut app, &[Type("d"), Type("y")]);
+    assert_shows(&app, "Personal Diary");
+    assert_shows(&app, "Entry deleted");
+    assert_hides(&app, "Met Sam");
+    assert_eq!(contents(&app), ["Walked the dog\nIt rained all day."]);
+}
+
+#[test]
+fn views_entries_in_turn() {
+    let mut app = app();
+    play(&mut app, &[Key(KeyCode::Enter)]);
+    assert_shows(&app, "Entry from 2024-03-01 09:30 (1 of 2)");
+    assert_shows(&app, "│Walked the dog");
+    assert_shows(&app, "│It rained all day.");
+
+    play(&mut app, &[Type("n")]);
+    assert_shows(&app, "Entry from 2024-03-02 09:30 (2 of 2)");
+    assert_shows(&app, "│Met Sam for lunch");
+
+    // Back on the main screen, the entry viewed last is selected.
+    play(&mut app, &[Key(KeyCode::Esc)]);
+    assert_shows(&app, "> Met Sam for lunch");
+}
+
+#[test]
+fn searches_and_opens_a_result() {
+    let mut app = app();
+    play(&mut app, &[Type("s"), Type("lunch")]);
+    assert_shows(&app, "Search Entries");
+    assert_shows(&app, "│lunch");
+
+    play(&mut app, &[Key(KeyCode::Enter)]);
+    assert_shows(&app, "Search Results");
+    assert_shows(&app, "Results: 1");
+    assert_shows(&app, "> [2024-03-02 09:30] Met Sam for lunch (1 match)");
+    assert_hides(&app, "Walked the dog");
+
+    play(&mut app, &[Key(KeyCode::Enter)]);
+    assert_shows(&app, "Entry from 2024-03-02 09:30");
+
+    play(&mut app, &[Key(KeyCode::Esc), Key(KeyCode::Esc)]);
+    assert_shows(&app, "Personal Diary");
+}
+
+#[test]
+fn searches_tags_by_regex() {
+    let mut app = app();
+    play(
+        &mut app,
+        &[
+            Type("s"),
+            Key(KeyCode::Tab),
+            Type("^f"),
+            Key(KeyCode::Enter),
+        ],
+    );
+    assert_shows(&app, "Results: 1");
+    assert_shows(&app, "Tags: friends, food");
+}
+
+#[test]
+fn invalid_regex_is_reported() {
+    let mut app = app();
+    play(
+        &mut app,
+        &[
+            Type("s"),
+            Key(KeyCode::Tab),
+            Type("(dog"),
+            Key(KeyCode::Enter),
+        ],
+    );
+    assert_shows(&app, "Search Query (regex)");
+    assert_hides(&app, "Search Results");
+}
+
+#[test]
+fn saves_and_deletes_a_search() {
+    let mut app = app();
+    play(
+        &mut app,
+        &[
+            Type("s"),
+            Type("dog"),
+            Key(KeyCode::Enter),
+            Type("s"),
+            Type("Dogs"),
+            Key(KeyCode::Enter),
+        ],
+    );
+    assert_shows(&app, "Personal Diary");
+    assert_shows(&app, "Search saved");
+    assert_shows(&app, "│1 Dogs (1)");
+
+    play(&mut app, &[Type("1")]);
+    assert_shows(&app, "Dogs");
+    assert_shows(&app, "x: Delete saved");
+    assert_shows(&app, "> [2024-03-01 09:30] Walked the dog (1 match)");
+
+    play(&mut app, &[Type("x")]);
+    assert_hides(&app, "Saved Searches");
+    assert!(app.ctx.diary.get_saved_searches().is_empty());
+}
+
+#[test]
+fn reaches_saved_searches_past_the_ninth() {
+    let mut app = app_with(
+        vec![entry(1, "Walked the dog", &[])],
+        Drafts::in_memory(),
+        WIDTH,
+        40,
+    );
+    for n in 1..=10 {
+        app.ctx.diary.add_saved_search(SavedSearch {
+            name: format!("Search {}", n),
+            query: SearchQuery {
+                text: "dog".to_string(),
+                mode: SearchMode::Plain,
+            },
+        });
+    }
+    play(&mut app, &[]);
+    assert_shows(&app, "Tab or 1-9 for saved searches");
+    assert_hides(&app, "Search 10");
+
+    play(&mut app, &[Key(KeyCode::Tab)]);
+    for _ in 0..9 {
+        play(&mut app, &[Key(KeyCode::Down)]);
+    }
+    assert_shows(&app, "│>   Search 10 (1)");
+    play(&mut app, &[Key(KeyCode::Enter)]);
+    assert_shows(&app, "x: Delete saved");
+    assert_shows(&app, "Search 10");
+
+    play(&mut app, &[Key(KeyCode::Esc), Key(KeyCode::Delete)]);
+    assert_shows(&app, "Delete the saved search \"Search 10\"?");
+    play(&mut app, &[Type("y")]);
+    assert_shows(&app, "Saved search deleted");
+    assert_eq!(app.ctx.diary.get_saved_searches().len(), 9);
+}
+
+#[test]
+fn reopens_entries_at_the_same_line_at_any_width() {
+    let content: Vec<String> = (1..=40)
+        .map(|n| format!("Line {} {}", n, "word ".repeat(20)))
+        .collect();
+    let mut app = app_with(
+        vec![entry(1, &content.join("\n"), &[])],
+        Drafts::in_memory(),
+        WIDTH,
+        HEIGHT,
+    );
+    play(
+        &mut app,
+        &[Key(KeyCode::Enter), Type("jjjjjjjjjj"), Key(KeyCode::Esc)],
+    );
+    let line = app.ctx.diary.get_reading_position(1);
+    assert!(line > 0);
+
+    for (width, height) in [(WIDTH, HEIGHT), (120, 30), (60, 20)] {
+        app.terminal.backend_mut().resize(width, height);
+        play(&mut app, &[Key(KeyCode::Enter)]);
+        assert_shows(&app, &format!("Line {} word", line + 1));
+        assert_hides(&app, &format!("Line {} word", line));
+        play(&mut app, &[Key(KeyCode::Esc)]);
+        assert_eq!(app.ctx.diary.get_reading_position(1), line);
+    }
+}
+
+#[test]
+fn ticks_open_tasks() {
+    let mut app = app_with(
+        vec![entry(1, "Errands\n- [x] Post\n- [ ] Bank\n- [ ] Shop", &[])],
+        Drafts::in_memory(),
+        WIDTH,
+        HEIGHT,
+    );
+    play(&mut app, &[Type("t")]);
+    assert_shows(&app, "Unticked items: 2");
+    assert_shows(&app, "> [ ] Bank  2024-03-01 Errands");
+
+    play(&mut app, &[Type("x")]);
+    assert_shows(&app, "Unticked items: 1");
+    assert_eq!(
+        contents(&app),
+        ["Errands\n- [x] Post\n- [x] Bank\n- [ ] Shop"]
+    );
+}
+
+#[test]
+fn recovers_a_draft() {
+    let mut drafts = Drafts::in_memory();
+    drafts.put(Draft {
+        entry_id: None,
+        content: "Unfinished thought".to_string(),
+        tags: "ideas".to_string(),
+        mood: None,
+        saved_at: Local.with_ymd_and_hms(2024, 3, 3, 22, 15, 0).unwrap(),
+        date: None,
+        prompt: None,
+    });
+    let mut app = app_with(Vec::new(), drafts, WIDTH, HEIGHT);
+    play(&mut app, &[]);
+    assert_shows(&app, "Recover draft?");
+    assert_shows(&app, "The last autosave is from 2024-03-03 22:15");
+
+    play(&mut app, &[Type("y")]);
+    assert_shows(&app, "New Diary Entry");
+    assert_shows(&app, "│Unfinished thought");
+
+    play(&mut app, &[Ctrl('s')]);
+    assert_shows(&app, "Personal Diary");
+    assert_eq!(contents(&app), ["Unfinished thought"]);
+    assert_eq!(app.ctx.diary.get_entries()[0].tags, ["ideas"]);
+    assert!(app.ctx.drafts.get_drafts().is_empty());
+}
+
+#[test]
+fn calendar_opens_days_and_backdates_entries() {
+    let now = now();
+    let yesterday = now - chrono::Duration::days(1);
+    let mut app = app_with(
+        vec![
+            DiaryEntry {
+                timestamp: yesterday,
+                ..DiaryEntry::new(0, "Long walk by the river".to_string(), Vec::new())
+            },
+            DiaryEntry {
+                timestamp: now,
+                ..DiaryEntry::new(0, "Quiet day".to_string(), Vec::new())
+            },
+        ],
+        Drafts::in_memory(),
+        100,
+        30,
+    );
+    play(&mut app, &[Type("c")]);
+    assert_shows(&app, &now.format("%B %Y").to_string());
+    assert_shows(&app, "Quiet day");
+    assert_shows(&app, "2 words");
+
+    play(&mut app, &[Key(KeyCode::Left)]);
+    assert_shows(&app, &yesterday.format("%A, %-d %B %Y").to_string());
+    assert_shows(&app, "Long walk by the river");
+
+    play(&mut app, &[Key(KeyCode::Enter)]);
+    assert_shows(&app, "│Long walk by the river");
+
+    let day_before = now.date_naive() - chrono::Days::new(2);
+    play(&mut app, &[Key(KeyCode::Esc), Key(KeyCode::Left)]);
+    assert_shows(&app, "No entries. Press Enter to write one.");
+
+    play(&mut app, &[Key(KeyCode::Enter), Type("Catching up")]);
+    assert_shows(
+        &app,
+        &format!("New Diary Entry for {}", day_before.format("%A, %-d %B %Y")),
+    );
+
+    play(&mut app, &[Ctrl('s')]);
+    assert_shows(&app, "Calendar");
+    assert_shows(&app, "Catching up");
+    let added = app.ctx.diary.get_entries().last().unwrap();
+    assert_eq!(added.timestamp.date_naive(), day_before);
+}
+
+#[test]
+fn year_overview_drills_down_into_a_day() {
+    let now = now();
+    let mut mood_entry = DiaryEntry::new(0, "A very good day".to_string(), Vec::new());
+    mood_entry.timestamp = now;
+    mood_entry.mood = Some(5);
+    let mut app = app_with(vec![mood_entry], Drafts::in_memory(), 120, 30);
+    play(&mut app, &[Type("y")]);
+    assert_shows(&app, "Year in Pixels");
+    assert_shows(&app, &format!("{}", now.year()));
+    assert_shows(&app, "1 entry, 4 words on 1 day");
+    assert_shows(&app, "A very good day");
+
+    play(&mut app, &[Key(KeyCode::PageUp)]);
+    assert_shows(&app, &format!("{}", now.year() - 1));
+    assert_shows(&app, "0 entries, 0 words on 0 days");
+
+    play(&mut app, &[Type("t"), Key(KeyCode::Enter)]);
+    assert_shows(&app, "│A very good day");
+
+    play(&mut app, &[Key(KeyCode::Esc), Type("c")]);
+    assert_shows(&app, "Calendar");
+    assert_shows(&app, &now.format("%B %Y").to_string());
+}
+
+#[test]
+fn shows_statistics() {
+    let mut app = app_with(
+        vec![
+            entry(1, "Walked the dog\nIt rained all day.", &["pets"]),
+            entry(2, "Met Sam for lunch", &["friends", "pets"]),
+        ],
+        Drafts::in_memory(),
+        100,
+        30,
+    );
+    play(&mut app, &[Type("i")]);
+    assert_shows(&app, "Statistics");
+    assert_shows(&app, "2 entries, 11 words, 5 words per entry on average");
+    assert_shows(&app, "longest: 2 days");
+    assert_shows(&app, "pets                2");
+    assert_shows(&app, "Entries by hour");
+
+    play(&mut app, &[Key(KeyCode::Esc)]);
+    assert_shows(&app, "Personal Diary");
+}
+
+#[test]
+fn tracks_writing_goals() {
+    let mut diary = DiaryState::in_memory();
+    diary.add_entry(DiaryEntry {
+        timestamp: now(),
+        ..DiaryEntry::new(0, "Morning pages".to_string(), Vec::new())
+    });
+    let config = Config {
+        goals: Goals {
+            words_per_day: Some(5),
+            entries_per_week: Some(3),
+        },
+        ..Config::default()
+    };
+    let ctx = Context::new(
+        diary,
+        config,
+        Drafts::in_memory(),
+        Prompts::default(),
+        Vec::new(),
+    )
+    .at(now());
+    let terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap();
+    let mut app = App::new(terminal, Box::new(Script(VecDeque::new())), ctx);
+    play(&mut app, &[]);
+    assert_shows(&app, "2 of 5 words written today. Just 3 to go!");
+    assert_shows(&app, "1-day streak");
+    assert_shows(&app, "1/3 this week");
+
+    play(&mut app, &[Type("w"), Type("Then a long walk")]);
+    assert_shows(&app, "Content (4 words)");
+    assert_shows(&app, "Daily goal met: 6/5 words");
+
+    play(&mut app, &[Ctrl('s')]);
+    assert_shows(&app, "2/3 this week");
+}
+
+#[test]
+fn reminds_of_the_weekly_goal_once_the_daily_one_is_met() {
+    let reminder = |words_per_day| {
+        let mut diary = DiaryState::in_memory();
+        diary.add_entry(DiaryEntry {
+            timestamp: now(),
+            ..DiaryEntry::new(0, "Morning pages".to_string(), Vec::new())
+        });
+        let config = Config {
+            goals: Goals {
+                words_per_day,
+                entries_per_week: Some(3),
+            },
+            ..Config::default()
+        };
+        let ctx = Context::new(
+            diary,
+            config,
+            Drafts::in_memory(),
+            Prompts::default(),
+            Vec::new(),
+        )
+        .at(now());
+        goal_reminder(&ctx)
+    };
+    assert_eq!(
+        reminder(Some(2)).as_deref(),
+        Some("1 of 3 entries written this week. Just 2 to go!")
+    );
+    assert_eq!(
+        reminder(None).as_deref(),
+        Some("1 of 3 entries written this week. Just 2 to go!")
+    );
+    assert_eq!(
+        reminder(Some(5)).as_deref(),
+        Some("2 of 5 words written today. Just 3 to go!")
+    );
+}
+
+#[test]
+fn looks_back_on_this_day() {
+    let now = now();
+    let years_ago = |years: u32| DiaryEntry {
+        timestamp: now - chrono::Months::new(12 * years),
+        ..DiaryEntry::new(0, format!("Written {} years back", years), Vec::new())
+    };
+    let mut app = app_with(
+        vec![years_ago(2), years_ago(1)],
+        Drafts::in_memory(),
+        100,
+        30,
+    );
+    play(&mut app, &[]);
+    assert_shows(&app, "On this day");
+    assert_shows(&app, "1 year ago  Written 1 years back");
+    assert_shows(&app, "2 years ago Written 2 years back");
+
+    play(
+        &mut app,
+        &[Key(KeyCode::Tab), Key(KeyCode::Down), Key(KeyCode::Enter)],
+    );
+    assert_shows(&app, "│Written 2 years back");
+
+    play(&mut app, &[Key(KeyCode::Esc), Type("h")]);
+    assert_hides(&app, "1 year ago");
+    assert_shows(&app, "h to show/hide On this day");
+}
+
+#[test]
+fn answers_prompts_and_browses_the_answers() {
+    let mut app = app_with(Vec::new(), Drafts::in_memory(), 100, 30);
+    play(&mut app, &[Type("w")]);
+    let today = now().date_naive();
+    let of_the_day = app.ctx.prompts.of_the_day(today).unwrap().text.clone();
+    assert_shows(&app, &format!("Prompt of the day: {}", of_the_day));
+
+    // The prompt is only a suggestion until taken up.
+    play(&mut app, &[Type("Something else"), Ctrl('s')]);
+    assert_eq!(app.ctx.diary.get_entries()[0].prompt, None);
+
+    play(&mut app, &[Type("w"), Ctrl('t')]);
+    assert_shows(&app, &format!("Prompt: {}", of_the_day));
+    play(&mut app, &[Type("Plenty"), Ctrl('s')]);
+    let added = app.ctx.diary.get_entries().last().unwrap();
+    assert_eq!(added.prompt.as_deref(), Some(of_the_day.as_str()));
+
+    play(&mut app, &[Type("p")]);
+    assert_shows(&app, "Writing Prompts");
+    assert_shows(&app, "What are three things you're grateful for today?");
+
+    play(
+        &mut app,
+        &[Type("w"), Ctrl('t'), Type("Learned to juggle"), Ctrl('s')],
+    );
+    play(&mut app, &[Key(KeyCode::Down)]);
+    assert_shows(&app, "> Who made your day a little better, and how?");
+    assert_shows(&app, "Learned to juggle");
+    assert_eq!(
+        app.ctx
+            .diary
+            .get_entries()
+            .last()
+            .unwrap()
+            .prompt
+            .as_deref(),
+        Some("Who made your day a little better, and how?")
+    );
+
+    play(&mut app, &[Key(KeyCode::Enter)]);
+    assert_shows(&app, "│Learned to juggle");
+}
+
+#[test]
+fn starts_an_entry_from_a_template() {
+    let mut app = app();
+    app.ctx.templates = vec![Template::parse(
+        "standup",
+        "---\ntags: work\n---\n# Standup {{date}}\nToday: {{cursor}}\n",
+    )];
+    play(&mut app, &[Type("w")]);
+    assert_shows(&app, "New entry from");
+    assert_shows(&app, "> Blank entry");
+    assert_shows(&app, "standup  work");
+
+    play(
+        &mut app,
+        &[Key(KeyCode::Down), Key(KeyCode::Enter), Type("ship it")],
+    );
+    let today = now().format("%Y-%m-%d").to_string();
+    assert_shows(&app, &format!("│# Standup {}", today));
+    assert_shows(&app, "│Today: ship it");
+    assert_shows(&app, "│work");
+
+    play(&mut app, &[Ctrl('s')]);
+    let added = app.ctx.diary.get_entries().last().unwrap();
+    assert_eq!(
+        added.content,
+        format!("# Standup {}\nToday: ship it\n", today)
+    );
+    assert_eq!(added.tags, ["work"]);
+    assert_eq!(added.prompt, None);
+
+    play(&mut app, &[Type("w"), Key(KeyCode::Enter)]);
+    assert_shows(&app, "New Diary Entry");
+    assert_hides(&app, "Standup");
+}
+
+#[test]
+fn a_template_with_the_prompt_answers_it() {
+    let mut app = app();
+    app.ctx.templates = vec![Template::parse(
+        "reflect",
+        "> {{prompt}}
+{{cursor}}",
+    )];
+    let of_the_day = app.ctx.prompts.of_the_day(now().date_naive()).unwrap();
+    let of_the_day = of_the_day.text.clone();
+    play(
+        &mut app,
+        &[
+            Type("w"),
+            Key(KeyCode::Down),
+            Key(KeyCode::Enter),
+            Type("Thoughts"),
+            Ctrl('s'),
+        ],
+    );
+    let added = app.ctx.diary.get_entries().last().unwrap();
+    assert_eq!(added.content, format!("> {}\nThoughts", of_the_day));
+    assert_eq!(added.prompt, Some(of_the_day));
+}
@@ -0,0 +1,107 @@
+use pulldown_cmark::{Event, Options, Parser};
+
+/// A `- [ ]` or `- [x]` item in an entry.
+#[derive(Clone)]
+pub struct Task {
+    /// The line of the content it is on.
+    pub line: usize,
+    pub text: String,
+    pub done: bool,
+}
+
+/// The checklist items in `content`, in order. Only items the Markdown
+/// renderer shows as checkboxes count, so not those inside code blocks.
+pub fn tasks(content: &str) -> Vec<Task> {
+    let lines: Vec<&str> = content.split('\n').collect();
+    Parser::new_ext(content, Options::ENABLE_TASKLISTS)
+        .into_offset_iter()
+        .filter_map(|(event, range)| match event {
+            Event::TaskListMarker(done) => {
+                let line = content[..range.start].matches('\n').count();
+                // The text is whatever follows the box on its line.
+                let after = &lines[line][range.end - line_start(content, line)..];
+                Some(Task {
+                    line,
+                    text: after.trim().to_string(),
+                    done,
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// `content` with the checkbox on `line` ticked or unticked.
+pub fn toggle(content: &str, line: usize) -> String {
+    let mut lines: Vec<String> = content.split('\n').map(|l| l.to_string()).collect();
+    if let Some(text) = lines.get_mut(line) {
+        // The box comes first; the item's text may hold brackets too.
+        let found = ["[ ]", "[x]", "[X]"]
+            .iter()
+            .filter_map(|from| text.find(from).map(|at| (at, *from)))
+            .min();
+        if let Some((at, from)) = found {
+            let to = if from == "[ ]" { "[x]" } else { "[ ]" };
+            text.replace_range(at..at + from.len(), to);
+        }
+    }
+    lines.join("\n")
+}
+
+fn line_start(content: &str, line: usize) -> usize {
+    content.split_inclusive('\n').take(line).map(str::len).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(content: &str) -> Vec<(usize, String, bool)> {
+        tasks(content)
+            .into_iter()
+            .map(|task| (task.line, task.text, task.done))
+            .collect()
+    }
+
+    #[test]
+    fn finds_items_with_any_bullet_and_indent() {
+        let content =
+            "Errands\n- [ ] Post [urgent]\n* [x] Bank\n+ [X] Shop\n  - [ ] Milk\n\n1. [ ] Call";
+        assert_eq!(
+            found(content),
+            [
+                (1, "Post [urgent]".to_string(), false),
+                (2, "Bank".to_string(), true),
+                (3, "Shop".to_string(), true),
+                (4, "Milk".to_string(), false),
+                (6, "Call".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn leaves_out_boxes_in_code_and_plain_text() {
+        let content = "```\n- [ ] Not a task\n```\n[ ] Nor this\n- [ ] Task";
+        assert_eq!(found(content), [(4, "Task".to_string(), false)]);
+    }
+
+    #[test]
+    fn ticks_and_unticks_only_the_box() {
+        let content = "- [ ] One [x]\n  * [X] Two";
+        assert_eq!(toggle(content, 0), "- [x] One [x]\n  * [X] Two");
+        assert_eq!(toggle(content, 1), "- [ ] One [x]\n  * [ ] Two");
+        assert_eq!(toggle(content, 5), content);
+        assert_eq!(toggle("Ends here\n+ [ ] Last", 1), "Ends here\n+ [x] Last");
+        assert_eq!(toggle("- [x] Last\n", 0), "- [ ] Last\n");
+    }
+
+    #[test]
+    fn keeps_windows_line_endings() {
+        let content = "Intro\r\n- [ ] One\r\n- [x] Two\r\n";
+        assert_eq!(
+            found(content),
+            [(1, "One".to_string(), false), (2, "Two".to_string(), true)]
+        );
+        assert_eq!(toggle(content, 2), "Intro\r\n- [ ] One\r\n- [ ] Two\r\n");
+    }
+}
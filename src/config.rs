@@ -0,0 +1,39 @@
+use color_eyre::Result;
+use serde::Deserialize;
+use std::fs;
+
+/// Settings read from `diary_config.json`. Every field is optional.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub editing_mode: EditingMode,
+    pub goals: Goals,
+    /// Whether On this day also shows the entries of a week and a month ago.
+    pub on_this_day_recent: bool,
+}
+
+/// How much to aim to write. A goal that isn't set isn't shown.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct Goals {
+    pub words_per_day: Option<usize>,
+    pub entries_per_week: Option<usize>,
+}
+
+/// Key bindings used in the entry editor and the other text inputs.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EditingMode {
+    #[default]
+    Standard,
+    Vim,
+    Emacs,
+}
+
+impl Config {
+    pub fn load_from_file() -> Result<Self> {
+        let serialized = fs::read_to_string("diary_config.json")?;
+        let config = serde_json::from_str(&serialized)?;
+        Ok(config)
+    }
+}
@@ -0,0 +1,449 @@
+//! Parsing of the date expressions accepted by date filters and by the
+//! `after:`, `before:` and `date:` search filters.
+//!
+//! Every expression resolves to an inclusive range of days relative to a given
+//! `today`:
+//!
+//! - absolute dates: `2023-03-14`, `2023-03`, `2023`, `march 2023`, `march`
+//! - named days: `today`, `yesterday`, `tomorrow`, `monday` (most recent)
+//! - periods: `this week`, `last month`, `next year`, `last 7 days`
+//! - offsets: `3 days ago`, `2 weeks ago`, `1 month ago`
+//! - ranges of any two of the above: `2023-01..2023-03`, `last month..today`
+//!
+//! Words may also be joined with `-` or `_` (`last-week`, `3-days-ago`) so an
+//! expression fits in a single search term. Weeks start on Monday.
+
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateRange {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl DateRange {
+    fn day(date: NaiveDate) -> Self {
+        DateRange {
+            start: date,
+            end: date,
+        }
+    }
+
+    /// The week containing `date`, or `None` if part of it lies beyond the
+    /// dates chrono can represent.
+    fn week_of(date: NaiveDate) -> Option<Self> {
+        let start =
+            date.checked_sub_days(Days::new(date.weekday().num_days_from_monday() as u64))?;
+        Some(DateRange {
+            start,
+            end: start.checked_add_days(Days::new(6))?,
+        })
+    }
+
+    fn month_of(date: NaiveDate) -> Option<Self> {
+        let start = date.with_day(1)?;
+        Some(DateRange {
+            start,
+            end: start.checked_add_months(Months::new(1))?.pred_opt()?,
+        })
+    }
+
+    fn year_of(date: NaiveDate) -> Self {
+        DateRange {
+            start: NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap(),
+            end: NaiveDate::from_ymd_opt(date.year(), 12, 31).unwrap(),
+        }
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Unit {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl Unit {
+    fn parse(word: &str) -> Option<Self> {
+        match word.trim_end_matches('s') {
+            "day" => Some(Unit::Day),
+            "week" => Some(Unit::Week),
+            "month" => Some(Unit::Month),
+            "year" => Some(Unit::Year),
+            _ => None,
+        }
+    }
+
+    /// The period of this unit that contains `date`, if it can be
+    /// represented.
+    fn period_of(self, date: NaiveDate) -> Option<DateRange> {
+        match self {
+            Unit::Day => Some(DateRange::day(date)),
+            Unit::Week => DateRange::week_of(date),
+            Unit::Month => DateRange::month_of(date),
+            Unit::Year => Some(DateRange::year_of(date)),
+        }
+    }
+
+    /// Moves `date` by `amount` units, backwards when `amount` is negative.
+    fn shift(self, date: NaiveDate, amount: i64) -> Option<NaiveDate> {
+        let magnitude = amount.unsigned_abs();
+        let days = |per_unit: u64| magnitude.checked_mul(per_unit).map(Days::new);
+        let months = |per_unit: u32| {
+            u32::try_from(magnitude)
+                .ok()?
+                .checked_mul(per_unit)
+                .map(Months::new)
+        };
+        match (self, amount < 0) {
+            (Unit::Day, false) => date.checked_add_days(days(1)?),
+            (Unit::Day, true) => date.checked_sub_days(days(1)?),
+            (Unit::Week, false) => date.checked_add_days(days(7)?),
+            (Unit::Week, true) => date.checked_sub_days(days(7)?),
+            (Unit::Month, false) => date.checked_add_months(months(1)?),
+            (Unit::Month, true) => date.checked_sub_months(months(1)?),
+            (Unit::Year, false) => date.checked_add_months(months(12)?),
+            (Unit::Year, true) => date.checked_sub_months(months(12)?),
+        }
+    }
+}
+
+/// Parses `expression` into the range of days it describes, relative to
+/// `today`.
+pub fn parse(expression: &str, today: NaiveDate) -> Result<DateRange, String> {
+    let expression = expression.trim().to_lowercase();
+
+    if let Some((from, to)) = expression.split_once("..") {
+        let start = parse_single(from.trim(), today)?.start;
+        let end = parse_single(to.trim(), today)?.end;
+        if start > end {
+            return Err(format!("'{}' ends before it starts", expression));
+        }
+        return Ok(DateRange { start, end });
+    }
+
+    parse_single(&expression, today)
+}
+
+fn parse_single(expression: &str, today: NaiveDate) -> Result<DateRange, String> {
+    if expression.is_empty() {
+        return Err("empty date".to_string());
+    }
+
+    if let Some(range) = parse_absolute(expression) {
+        return Ok(range);
+    }
+
+    let normalized = expression.replace(['-', '_'], " ");
+    let words: Vec<&str> = normalized.split_whitespace().collect();
+    let invalid = || format!("unknown date '{}'", expression);
+
+    let range = match words.as_slice() {
+        ["today"] => DateRange::day(today),
+        ["yesterday"] => DateRange::day(today.pred_opt().ok_or_else(invalid)?),
+        ["tomorrow"] => DateRange::day(today.succ_opt().ok_or_else(invalid)?),
+        [relative @ ("this" | "last" | "next"), unit] => {
+            if let Some(unit) = Unit::parse(unit) {
+                let offset = match *relative {
+                    "this" => 0,
+                    "last" => -1,
+                    _ => 1,
+                };
+                unit.period_of(unit.shift(today, offset).ok_or_else(invalid)?)
+                    .ok_or_else(invalid)?
+            } else if let Some(weekday) = parse_weekday(unit) {
+                match *relative {
+                    "this" => {
+                        DateRange::day(weekday_on_or_before(today, weekday).ok_or_else(invalid)?)
+                    }
+                    "last" => DateRange::day(
+                        weekday_on_or_before(today.pred_opt().ok_or_else(invalid)?, weekday)
+                            .ok_or_else(invalid)?,
+                    ),
+                    _ => return Err(invalid()),
+                }
+            } else {
+                return Err(invalid());
+            }
+        }
+        ["last" | "past", count, unit] => {
+            let count: i64 = count.parse().map_err(|_| invalid())?;
+            let unit = Unit::parse(unit).ok_or_else(invalid)?;
+            if count == 0 {
+                return Err(invalid());
+            }
+            DateRange {
+                start: unit
+                    .shift(today, -(count - 1))
+                    .and_then(|d| unit.period_of(d))
+                    .ok_or_else(invalid)?
+                    .start,
+                end: unit.period_of(today).ok_or_else(invalid)?.end,
+            }
+        }
+        [count, unit, "ago"] => {
+            let count: i64 = count.parse().map_err(|_| invalid())?;
+            let unit = Unit::parse(unit).ok_or_else(invalid)?;
+            unit.shift(today, -count)
+                .and_then(|d| unit.period_of(d))
+                .ok_or_else(invalid)?
+        }
+        [word] => {
+            if let Some(weekday) = parse_weekday(word) {
+                DateRange::day(weekday_on_or_before(today, weekday).ok_or_else(invalid)?)
+            } else if let Some(month) = parse_month(word) {
+                NaiveDate::from_ymd_opt(today.year(), month, 1)
+                    .and_then(DateRange::month_of)
+                    .ok_or_else(invalid)?
+            } else {
+                return Err(invalid());
+            }
+        }
+        [month, year] => {
+            let month = parse_month(month).ok_or_else(invalid)?;
+            let year: i32 = year.parse().map_err(|_| invalid())?;
+            NaiveDate::from_ymd_opt(year, month, 1)
+                .and_then(DateRange::month_of)
+                .ok_or_else(invalid)?
+        }
+        _ => return Err(invalid()),
+    };
+
+    Ok(range)
+}
+
+/// `YYYY-MM-DD`, `YYYY-MM` and `YYYY`.
+fn parse_absolute(expression: &str) -> Option<DateRange> {
+    if let Ok(date) = NaiveDate::parse_from_str(expression, "%Y-%m-%d") {
+        return Some(DateRange::day(date));
+    }
+
+    let parts: Vec<&str> = expression.split('-').collect();
+    if !parts
+        .iter()
+        .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+    {
+        return None;
+    }
+    match parts.as_slice() {
+        [year] if year.len() == 4 => Some(DateRange::year_of(NaiveDate::from_ymd_opt(
+            year.parse().ok()?,
+            1,
+            1,
+        )?)),
+        [year, month] if year.len() == 4 => DateRange::month_of(NaiveDate::from_ymd_opt(
+            year.parse().ok()?,
+            month.parse().ok()?,
+            1,
+        )?),
+        _ => None,
+    }
+}
+
+fn parse_weekday(word: &str) -> Option<Weekday> {
+    match word {
+        "mon" | "monday" => Some(Weekday::Mon),
+        "tue" | "tuesday" => Some(Weekday::Tue),
+        "wed" | "wednesday" => Some(Weekday::Wed),
+        "thu" | "thursday" => Some(Weekday::Thu),
+        "fri" | "friday" => Some(Weekday::Fri),
+        "sat" | "saturday" => Some(Weekday::Sat),
+        "sun" | "sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn parse_month(word: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = [
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+    ];
+
+    MONTHS
+        .iter()
+        .position(|m| word.len() >= 3 && m.starts_with(word))
+        .map(|i| i as u32 + 1)
+}
+
+fn weekday_on_or_before(date: NaiveDate, weekday: Weekday) -> Option<NaiveDate> {
+    let days_back =
+        (7 + date.weekday().num_days_from_monday() - weekday.num_days_from_monday()) % 7;
+    date.checked_sub_days(Days::new(days_back as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn range(start: NaiveDate, end: NaiveDate) -> DateRange {
+        DateRange { start, end }
+    }
+
+    // A Wednesday.
+    fn today() -> NaiveDate {
+        date(2024, 3, 13)
+    }
+
+    #[test]
+    fn parses_absolute_dates() {
+        assert_eq!(
+            parse("2023-03-14", today()),
+            Ok(range(date(2023, 3, 14), date(2023, 3, 14)))
+        );
+        assert_eq!(
+            parse("2023-02", today()),
+            Ok(range(date(2023, 2, 1), date(2023, 2, 28)))
+        );
+        assert_eq!(
+            parse("2023", today()),
+            Ok(range(date(2023, 1, 1), date(2023, 12, 31)))
+        );
+        assert_eq!(
+            parse("March 2023", today()),
+            Ok(range(date(2023, 3, 1), date(2023, 3, 31)))
+        );
+        assert_eq!(
+            parse("feb", today()),
+            Ok(range(date(2024, 2, 1), date(2024, 2, 29)))
+        );
+    }
+
+    #[test]
+    fn parses_named_days() {
+        assert_eq!(parse("today", today()), Ok(DateRange::day(today())));
+        assert_eq!(
+            parse("Yesterday", today()),
+            Ok(DateRange::day(date(2024, 3, 12)))
+        );
+        assert_eq!(
+            parse("tomorrow", today()),
+            Ok(DateRange::day(date(2024, 3, 14)))
+        );
+        assert_eq!(
+            parse("monday", today()),
+            Ok(DateRange::day(date(2024, 3, 11)))
+        );
+        assert_eq!(parse("wednesday", today()), Ok(DateRange::day(today())));
+        assert_eq!(
+            parse("last wednesday", today()),
+            Ok(DateRange::day(date(2024, 3, 6)))
+        );
+    }
+
+    #[test]
+    fn parses_relative_periods() {
+        assert_eq!(
+            parse("this week", today()),
+            Ok(range(date(2024, 3, 11), date(2024, 3, 17)))
+        );
+        assert_eq!(
+            parse("last week", today()),
+            Ok(range(date(2024, 3, 4), date(2024, 3, 10)))
+        );
+        assert_eq!(
+            parse("last-month", today()),
+            Ok(range(date(2024, 2, 1), date(2024, 2, 29)))
+        );
+        assert_eq!(
+            parse("next year", today()),
+            Ok(range(date(2025, 1, 1), date(2025, 12, 31)))
+        );
+        assert_eq!(
+            parse("last 7 days", today()),
+            Ok(range(date(2024, 3, 7), today()))
+        );
+        assert_eq!(
+            parse("past 2 months", today()),
+            Ok(range(date(2024, 2, 1), date(2024, 3, 31)))
+        );
+    }
+
+    #[test]
+    fn parses_offsets() {
+        assert_eq!(
+            parse("3 days ago", today()),
+            Ok(DateRange::day(date(2024, 3, 10)))
+        );
+        assert_eq!(
+            parse("1-day-ago", today()),
+            Ok(DateRange::day(date(2024, 3, 12)))
+        );
+        assert_eq!(
+            parse("2 weeks ago", today()),
+            Ok(range(date(2024, 2, 26), date(2024, 3, 3)))
+        );
+        assert_eq!(
+            parse("1 month ago", today()),
+            Ok(range(date(2024, 2, 1), date(2024, 2, 29)))
+        );
+    }
+
+    #[test]
+    fn month_arithmetic_clamps_to_month_end() {
+        assert_eq!(
+            parse("1 month ago", date(2024, 3, 31)),
+            Ok(range(date(2024, 2, 1), date(2024, 2, 29)))
+        );
+    }
+
+    #[test]
+    fn parses_ranges() {
+        assert_eq!(
+            parse("2023-01..2023-03", today()),
+            Ok(range(date(2023, 1, 1), date(2023, 3, 31)))
+        );
+        assert_eq!(
+            parse("last week .. today", today()),
+            Ok(range(date(2024, 3, 4), today()))
+        );
+        assert!(parse("today..last year", today()).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_expressions() {
+        assert!(parse("", today()).is_err());
+        assert!(parse("someday", today()).is_err());
+        assert!(parse("2023-13", today()).is_err());
+        assert!(parse("three days ago", today()).is_err());
+        assert!(parse("last 0 days", today()).is_err());
+        assert!(parse("next monday", today()).is_err());
+    }
+
+    #[test]
+    fn rejects_dates_out_of_range() {
+        assert!(parse("3000000000000000000 weeks ago", today()).is_err());
+        assert!(parse("400000000 years ago", today()).is_err());
+        assert!(parse("5000000000 months ago", today()).is_err());
+        assert!(parse("december 262142", today()).is_err());
+        assert!(parse("last 99999999999 days", today()).is_err());
+        assert!(parse("monday", NaiveDate::MIN).is_err());
+        assert!(parse("this week", NaiveDate::MAX).is_err());
+    }
+
+    #[test]
+    fn range_contains_its_bounds() {
+        let week = parse("this week", today()).unwrap();
+        assert!(week.contains(date(2024, 3, 11)));
+        assert!(week.contains(date(2024, 3, 17)));
+        assert!(!week.contains(date(2024, 3, 18)));
+    }
+}
@@ -7,6 +7,14 @@ pub struct DiaryEntry {
     pub timestamp: DateTime<Local>,
     pub content: String,
     pub tags: Vec<String>,
+    #[serde(default)]
+    pub modified: Option<DateTime<Local>>,
+    /// How the day went, from 1 (bad) to 5 (great).
+    #[serde(default)]
+    pub mood: Option<u8>,
+    /// The writing prompt the entry answers, if any.
+    #[serde(default)]
+    pub prompt: Option<String>,
 }
 
 impl DiaryEntry {
@@ -16,6 +24,18 @@ impl DiaryEntry {
             timestamp: Local::now(),
             content,
             tags,
+            modified: None,
+            mood: None,
+            prompt: None,
         }
     }
+
+    /// The first line of the entry, which the lists use as its title.
+    pub fn title(&self) -> &str {
+        self.content.lines().next().unwrap_or("")
+    }
+
+    pub fn word_count(&self) -> usize {
+        self.content.split_whitespace().count()
+    }
 }
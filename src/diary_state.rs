@@ -1,12 +1,33 @@
 use crate::diary_entry::DiaryEntry;
+use crate::list_order::{ListOrder, ListScreen};
+use crate::related::RelatedIndex;
+use crate::search::{CompiledSearch, SavedSearch};
+use chrono::{DateTime, Local};
 use color_eyre::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 
 #[derive(Serialize, Deserialize)]
 pub struct DiaryState {
     entries: Vec<DiaryEntry>,
     next_id: usize,
+    #[serde(default)]
+    saved_searches: Vec<SavedSearch>,
+    #[serde(default)]
+    list_orders: HashMap<ListScreen, ListOrder>,
+    /// How far each entry was scrolled when last read, by entry id.
+    #[serde(default)]
+    reading_positions: HashMap<usize, usize>,
+    #[serde(skip)]
+    related: RelatedIndex,
+    /// Goes up with every change to the entries or saved searches, so that
+    /// what is worked out from them can be kept until then.
+    #[serde(skip)]
+    revision: u64,
+    /// Keeps the diary off the disk, for tests.
+    #[serde(skip)]
+    in_memory: bool,
 }
 
 impl DiaryState {
@@ -14,25 +35,57 @@ impl DiaryState {
         DiaryState {
             entries: Vec::new(),
             next_id: 1,
+            saved_searches: Vec::new(),
+            list_orders: HashMap::new(),
+            reading_positions: HashMap::new(),
+            related: RelatedIndex::default(),
+            revision: 0,
+            in_memory: false,
+        }
+    }
+
+    /// A diary that is never saved.
+    #[cfg(test)]
+    pub fn in_memory() -> Self {
+        DiaryState {
+            in_memory: true,
+            ..DiaryState::new()
         }
     }
 
     pub fn add_entry(&mut self, mut entry: DiaryEntry) {
         entry.id = self.next_id;
         self.next_id += 1;
+        self.related.upsert(&entry);
         self.entries.push(entry);
+        self.revision += 1;
         self.save_to_file().unwrap();
     }
 
-    pub fn update_entry(&mut self, updated_entry: DiaryEntry) {
+    /// Replaces the entry with the same id, stamping it as modified at `now`
+    /// if its content, tags or mood changed. An unchanged entry is left as
+    /// it was.
+    pub fn update_entry(&mut self, mut updated_entry: DiaryEntry, now: DateTime<Local>) {
         if let Some(entry) = self.entries.iter_mut().find(|e| e.id == updated_entry.id) {
+            let changed = entry.content != updated_entry.content
+                || entry.tags != updated_entry.tags
+                || entry.mood != updated_entry.mood;
+            if !changed {
+                return;
+            }
+            updated_entry.modified = Some(now);
+            self.related.upsert(&updated_entry);
             *entry = updated_entry;
+            self.revision += 1;
             self.save_to_file().unwrap();
         }
     }
 
     pub fn delete_entry(&mut self, id: usize) {
         self.entries.retain(|e| e.id != id);
+        self.related.remove(id);
+        self.reading_positions.remove(&id);
+        self.revision += 1;
         self.save_to_file().unwrap();
     }
 
@@ -40,20 +93,78 @@ impl DiaryState {
         &self.entries
     }
 
-    pub fn search_entries(&self, query: &str) -> Vec<DiaryEntry> {
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// The entries most similar to entry `id`, with their similarity score.
+    pub fn related_entries(&self, id: usize, limit: usize) -> Vec<(&DiaryEntry, f64)> {
+        self.related
+            .related(id, limit)
+            .into_iter()
+            .filter_map(|(related_id, score)| {
+                self.entries
+                    .iter()
+                    .find(|e| e.id == related_id)
+                    .map(|e| (e, score))
+            })
+            .collect()
+    }
+
+    pub fn search(&self, query: &CompiledSearch) -> Vec<DiaryEntry> {
         self.entries
             .iter()
-            .filter(|e| {
-                e.content.to_lowercase().contains(&query.to_lowercase())
-                    || e.tags
-                        .iter()
-                        .any(|t| t.to_lowercase().contains(&query.to_lowercase()))
-            })
+            .filter(|e| query.matches(e))
             .cloned()
             .collect()
     }
 
+    pub fn get_saved_searches(&self) -> &Vec<SavedSearch> {
+        &self.saved_searches
+    }
+
+    pub fn add_saved_search(&mut self, saved_search: SavedSearch) {
+        self.saved_searches.push(saved_search);
+        self.revision += 1;
+        self.save_to_file().unwrap();
+    }
+
+    pub fn delete_saved_search(&mut self, index: usize) {
+        if index < self.saved_searches.len() {
+            self.saved_searches.remove(index);
+            self.revision += 1;
+            self.save_to_file().unwrap();
+        }
+    }
+
+    pub fn get_list_order(&self, screen: ListScreen) -> ListOrder {
+        self.list_orders.get(&screen).copied().unwrap_or_default()
+    }
+
+    pub fn set_list_order(&mut self, screen: ListScreen, order: ListOrder) {
+        self.list_orders.insert(screen, order);
+        self.save_to_file().unwrap();
+    }
+
+    pub fn get_reading_position(&self, id: usize) -> usize {
+        self.reading_positions.get(&id).copied().unwrap_or(0)
+    }
+
+    pub fn set_reading_position(&mut self, id: usize, position: usize) {
+        let previous = if position == 0 {
+            self.reading_positions.remove(&id)
+        } else {
+            self.reading_positions.insert(id, position)
+        };
+        if previous.unwrap_or(0) != position {
+            self.save_to_file().unwrap();
+        }
+    }
+
     pub fn save_to_file(&self) -> Result<()> {
+        if self.in_memory {
+            return Ok(());
+        }
         let serialized = serde_json::to_string(&self)?;
         fs::write("diary_entries.json", serialized)?;
         Ok(())
@@ -61,7 +172,8 @@ impl DiaryState {
 
     pub fn load_from_file() -> Result<Self> {
         let serialized = fs::read_to_string("diary_entries.json")?;
-        let diary_state: DiaryState = serde_json::from_str(&serialized)?;
+        let mut diary_state: DiaryState = serde_json::from_str(&serialized)?;
+        diary_state.related = RelatedIndex::build(&diary_state.entries);
         Ok(diary_state)
     }
 }
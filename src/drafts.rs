@@ -0,0 +1,96 @@
+use chrono::{DateTime, Local, NaiveDate};
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::ErrorKind;
+
+/// The unsaved state of an entry being written or edited.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Draft {
+    /// The entry being edited, or `None` for a new one.
+    pub entry_id: Option<usize>,
+    pub content: String,
+    /// The tags input as typed.
+    pub tags: String,
+    pub mood: Option<u8>,
+    pub saved_at: DateTime<Local>,
+    /// The earlier day a new entry is being written for, if any.
+    #[serde(default)]
+    pub date: Option<NaiveDate>,
+    /// The writing prompt being answered, if any.
+    #[serde(default)]
+    pub prompt: Option<String>,
+}
+
+impl Draft {
+    /// Whether the draft holds the same text, mood and prompt as `other`,
+    /// whenever each was saved.
+    pub fn same_text(&self, other: &Draft) -> bool {
+        self.content == other.content
+            && self.tags == other.tags
+            && self.mood == other.mood
+            && self.prompt == other.prompt
+    }
+}
+
+/// Drafts autosaved by the entry editor to `diary_drafts.json`, so that an
+/// entry survives the terminal closing. A draft is removed once its entry is
+/// saved or discarded, so any left at launch were interrupted.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Drafts {
+    drafts: Vec<Draft>,
+    /// Keeps the drafts off the disk, for tests.
+    #[serde(skip)]
+    in_memory: bool,
+}
+
+impl Drafts {
+    /// Drafts that are never saved.
+    #[cfg(test)]
+    pub fn in_memory() -> Self {
+        Drafts {
+            drafts: Vec::new(),
+            in_memory: true,
+        }
+    }
+
+    pub fn get_drafts(&self) -> &Vec<Draft> {
+        &self.drafts
+    }
+
+    /// Stores `draft`, replacing any earlier draft of the same entry.
+    pub fn put(&mut self, draft: Draft) {
+        self.drafts.retain(|d| d.entry_id != draft.entry_id);
+        self.drafts.push(draft);
+        self.save_to_file().unwrap();
+    }
+
+    pub fn remove(&mut self, entry_id: Option<usize>) {
+        let count = self.drafts.len();
+        self.drafts.retain(|d| d.entry_id != entry_id);
+        if self.drafts.len() != count {
+            self.save_to_file().unwrap();
+        }
+    }
+
+    pub fn save_to_file(&self) -> Result<()> {
+        if self.in_memory {
+            return Ok(());
+        }
+        if self.drafts.is_empty() {
+            match fs::remove_file("diary_drafts.json") {
+                Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+                _ => return Ok(()),
+            }
+        }
+        let serialized = serde_json::to_string(&self)?;
+        fs::write("diary_drafts.json", serialized)?;
+        Ok(())
+    }
+
+    pub fn load_from_file() -> Result<Self> {
+        let serialized = fs::read_to_string("diary_drafts.json")?;
+        let drafts = serde_json::from_str(&serialized)?;
+        Ok(drafts)
+    }
+}
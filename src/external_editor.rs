@@ -0,0 +1,223 @@
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, ErrorKind, Write};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A file only its owner can read, removed when dropped.
+struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    /// Creates a new file in the temporary directory. It is never an
+    /// existing file or a symlink someone planted at a guessed name.
+    fn create() -> io::Result<(Self, File)> {
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+
+        let mut attempt = 0;
+        loop {
+            let nanos = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.subsec_nanos());
+            let path = env::temp_dir().join(format!(
+                "diary-entry-{}-{}-{}.md",
+                std::process::id(),
+                nanos,
+                attempt
+            ));
+            match options.open(&path) {
+                Ok(file) => return Ok((TempFile { path }, file)),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists && attempt < 100 => attempt += 1,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// What can be edited in the external editor: the content, with its first
+/// line as the title in the front matter, the tags and the mood.
+pub struct Composed {
+    pub content: String,
+    pub tags: Vec<String>,
+    pub mood: Option<u8>,
+}
+
+/// Writes `composed` to a temporary file, opens it in `$VISUAL`, `$EDITOR`
+/// or `vi`, and reads it back once the editor exits. The file is private to
+/// the user and removed afterwards, whatever happens. The terminal must be
+/// out of raw mode while this runs.
+pub fn edit(composed: &Composed) -> Result<Composed, String> {
+    let (temp, mut file) =
+        TempFile::create().map_err(|e| format!("Could not create a temporary file: {}", e))?;
+    let path = &temp.path;
+    file.write_all(to_front_matter(composed).as_bytes())
+        .map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+    drop(file);
+
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .ok()
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+    // The variable may hold arguments too, as in `code --wait`.
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+    let status = Command::new(program).args(words).arg(path).status();
+
+    match status {
+        Ok(status) if status.success() => fs::read_to_string(path)
+            .map(|text| from_front_matter(&text))
+            .map_err(|e| format!("Could not read {}: {}", path.display(), e)),
+        Ok(status) => Err(format!("{} exited with {}", program, status)),
+        Err(e) => Err(format!("Could not run {}: {}", program, e)),
+    }
+}
+
+/// The file for `composed`. The body below the front matter is the content
+/// after its first line, exactly, and is left out if there is no second line.
+fn to_front_matter(composed: &Composed) -> String {
+    let (title, body) = match composed.content.split_once('\n') {
+        Some((title, body)) => (title, Some(body)),
+        None => (composed.content.as_str(), None),
+    };
+    let mood = composed.mood.map_or(String::new(), |m| m.to_string());
+    let mut text = format!(
+        "---\ntitle: {}\ntags: {}\nmood: {}\n---\n",
+        title,
+        composed.tags.join(", "),
+        mood,
+    );
+    if let Some(body) = body {
+        text.push_str(body);
+        text.push('\n');
+    }
+    text
+}
+
+/// Reads the file back, whichever line endings the editor saved it with.
+/// Without front matter the whole file is the content and there are no tags;
+/// unknown fields and an invalid mood are ignored. Without a title the body
+/// alone is the content.
+fn from_front_matter(text: &str) -> Composed {
+    let text = text.replace("\r\n", "\n");
+    let text = text.strip_suffix('\n').unwrap_or(&text);
+    let mut composed = Composed {
+        content: text.to_string(),
+        tags: Vec::new(),
+        mood: None,
+    };
+    let Some(rest) = text.strip_prefix("---\n") else {
+        return composed;
+    };
+    let (header, body) = match rest.split_once("\n---\n") {
+        Some((header, body)) => (header, Some(body)),
+        None => match rest.strip_suffix("\n---") {
+            Some(header) => (header, None),
+            None => return composed,
+        },
+    };
+
+    let mut title = None;
+    for line in header.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        match key.trim() {
+            // The title keeps its own spaces; only the one after the colon goes.
+            "title" => title = Some(value.strip_prefix(' ').unwrap_or(value)),
+            "tags" => {
+                composed.tags = value
+                    .split(',')
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty())
+                    .collect();
+            }
+            "mood" => composed.mood = value.trim().parse().ok().filter(|m| (1..=5).contains(m)),
+            _ => {}
+        }
+    }
+    composed.content = match (title, body) {
+        (Some(title), Some(body)) => format!("{}\n{}", title, body),
+        (Some(title), None) => title.to_string(),
+        (None, body) => body.unwrap_or_default().to_string(),
+    };
+    composed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn temporary_files_are_private_and_removed() {
+        let (temp, _) = TempFile::create().unwrap();
+        let (other, _) = TempFile::create().unwrap();
+        assert_ne!(temp.path, other.path);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&temp.path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        let path = temp.path.clone();
+        drop(temp);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn content_survives_the_round_trip_exactly() {
+        for content in [
+            "",
+            "Title",
+            "Title\n",
+            "Title\nBody",
+            "Title\nBody\n",
+            "Title\n\nBody\n\n",
+            "\nNo title",
+            "\n",
+            " Indented title \nBody",
+            "  ",
+        ] {
+            let composed = Composed {
+                content: content.to_string(),
+                tags: vec!["a".to_string(), "b c".to_string()],
+                mood: Some(4),
+            };
+            let read = from_front_matter(&to_front_matter(&composed));
+            assert_eq!(read.content, content);
+            assert_eq!(read.tags, composed.tags);
+            assert_eq!(read.mood, Some(4));
+        }
+    }
+
+    #[test]
+    fn reads_files_written_by_hand() {
+        let read = from_front_matter("---\ntags: x\nmood: 9\n---\nJust a body\n");
+        assert_eq!(read.content, "Just a body");
+        assert_eq!(read.tags, ["x"]);
+        assert_eq!(read.mood, None);
+
+        let windows =
+            from_front_matter("---\r\ntitle: Title\r\ntags: x\r\nmood: 2\r\n---\r\nBody\r\n");
+        assert_eq!(windows.content, "Title\nBody");
+        assert_eq!(windows.tags, ["x"]);
+        assert_eq!(windows.mood, Some(2));
+
+        let plain = from_front_matter("No front matter\n");
+        assert_eq!(plain.content, "No front matter");
+        assert!(plain.tags.is_empty());
+    }
+}
@@ -0,0 +1,275 @@
+use crate::config::EditingMode;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Movement {
+    Left,
+    Right,
+    WordLeft,
+    WordRight,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    LineStart,
+    LineEnd,
+    Start,
+    End,
+}
+
+/// What a key means, whichever profile it was typed in. Screens and text
+/// fields act on these rather than on key codes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    /// A printable character: typed into text fields, a shortcut elsewhere.
+    Char(char),
+    Confirm,
+    Cancel,
+    NextField,
+    Move(Movement),
+    /// Moves while extending the selection.
+    Select(Movement),
+    SelectAll,
+    /// Starts selecting with the following movements, Emacs style.
+    SetMark,
+    DeleteBackward,
+    DeleteForward,
+    KillToLineEnd,
+    KillWordBackward,
+    KillWordForward,
+    /// Kills the selection, or the word before the cursor if there is none.
+    KillRegion,
+    CopyRegion,
+    Yank,
+    /// Replaces the text just yanked with the previous kill.
+    YankPop,
+    Undo,
+    Redo,
+    /// Opens the entry in `$VISUAL` or `$EDITOR`.
+    ExternalEditor,
+    /// Shows or hides the rendered Markdown next to the entry.
+    TogglePreview,
+    /// Swaps the writing prompt for the next one.
+    NextPrompt,
+    Save,
+}
+
+/// Translates keys into [`Command`]s for the configured profile. Vim mode
+/// uses the standard keys outside its own normal and visual modes.
+#[derive(Clone, Copy)]
+pub struct Keymap {
+    profile: EditingMode,
+}
+
+impl Keymap {
+    pub fn new(profile: EditingMode) -> Self {
+        Keymap { profile }
+    }
+
+    pub fn profile(&self) -> EditingMode {
+        self.profile
+    }
+
+    pub fn command(&self, key: KeyEvent) -> Option<Command> {
+        if self.profile == EditingMode::Emacs {
+            if let Some(command) = emacs_command(key) {
+                return Some(command);
+            }
+        }
+        standard_command(key)
+    }
+}
+
+fn standard_command(key: KeyEvent) -> Option<Command> {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let alt = key.modifiers.contains(KeyModifiers::ALT);
+    let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+
+    let movement = match key.code {
+        KeyCode::Left if ctrl => Movement::WordLeft,
+        KeyCode::Right if ctrl => Movement::WordRight,
+        KeyCode::Left => Movement::Left,
+        KeyCode::Right => Movement::Right,
+        KeyCode::Up => Movement::Up,
+        KeyCode::Down => Movement::Down,
+        KeyCode::PageUp => Movement::PageUp,
+        KeyCode::PageDown => Movement::PageDown,
+        KeyCode::Home if ctrl => Movement::Start,
+        KeyCode::End if ctrl => Movement::End,
+        KeyCode::Home => Movement::LineStart,
+        KeyCode::End => Movement::LineEnd,
+        _ => {
+            return match key.code {
+                KeyCode::Char('a') if ctrl => Some(Command::SelectAll),
+                KeyCode::Char('z') if ctrl => Some(Command::Undo),
+                KeyCode::Char('y') if ctrl => Some(Command::Redo),
+                KeyCode::Char('o') if ctrl => Some(Command::ExternalEditor),
+                KeyCode::Char('s') if ctrl => Some(Command::Save),
+                KeyCode::Char('p') if ctrl => Some(Command::TogglePreview),
+                KeyCode::Char('t') if ctrl => Some(Command::NextPrompt),
+                KeyCode::Char(c) if !ctrl && !alt => Some(Command::Char(c)),
+                KeyCode::Enter => Some(Command::Confirm),
+                KeyCode::Esc => Some(Command::Cancel),
+                KeyCode::Tab | KeyCode::BackTab => Some(Command::NextField),
+                KeyCode::Backspace if ctrl || alt => Some(Command::KillWordBackward),
+                KeyCode::Backspace => Some(Command::DeleteBackward),
+                KeyCode::Delete => Some(Command::DeleteForward),
+                _ => None,
+            };
+        }
+    };
+    Some(if shift {
+        Command::Select(movement)
+    } else {
+        Command::Move(movement)
+    })
+}
+
+/// The Emacs and readline bindings, where they differ from the standard ones.
+fn emacs_command(key: KeyEvent) -> Option<Command> {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+    let alt = key.modifiers.contains(KeyModifiers::ALT);
+
+    let command = match key.code {
+        KeyCode::Char(c) if ctrl && !alt => match c {
+            'a' => Command::Move(Movement::LineStart),
+            'e' => Command::Move(Movement::LineEnd),
+            'f' => Command::Move(Movement::Right),
+            'b' => Command::Move(Movement::Left),
+            'n' => Command::Move(Movement::Down),
+            'p' => Command::Move(Movement::Up),
+            'v' => Command::Move(Movement::PageDown),
+            'd' => Command::DeleteForward,
+            'h' => Command::DeleteBackward,
+            'k' => Command::KillToLineEnd,
+            'w' => Command::KillRegion,
+            'y' => Command::Yank,
+            'g' => Command::Cancel,
+            ' ' | '@' => Command::SetMark,
+            // Terminals send Ctrl+/ and Ctrl+_ as Ctrl+7.
+            '/' | '_' | '7' => Command::Undo,
+            _ => return None,
+        },
+        KeyCode::Char(c) if alt && !ctrl => match c {
+            'f' => Command::Move(Movement::WordRight),
+            'b' => Command::Move(Movement::WordLeft),
+            'v' => Command::Move(Movement::PageUp),
+            '<' => Command::Move(Movement::Start),
+            '>' => Command::Move(Movement::End),
+            'd' => Command::KillWordForward,
+            'w' => Command::CopyRegion,
+            'y' => Command::YankPop,
+            'p' => Command::TogglePreview,
+            '_' => Command::Redo,
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text_area::{KillRing, Pos, TextArea};
+
+    fn ctrl(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    fn alt(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::ALT)
+    }
+
+    /// Types `keys` into `area` through the Emacs profile.
+    fn type_keys(area: &mut TextArea, kill_ring: &mut KillRing, keys: &[KeyEvent]) {
+        let keymap = Keymap::new(EditingMode::Emacs);
+        for &key in keys {
+            if let Some(command) = keymap.command(key) {
+                area.apply(command, kill_ring);
+            }
+        }
+    }
+
+    #[test]
+    fn emacs_bindings_fall_back_to_the_standard_ones() {
+        let emacs = Keymap::new(EditingMode::Emacs);
+        let standard = Keymap::new(EditingMode::Standard);
+        assert_eq!(
+            emacs.command(ctrl('a')),
+            Some(Command::Move(Movement::LineStart))
+        );
+        assert_eq!(standard.command(ctrl('a')), Some(Command::SelectAll));
+        assert_eq!(
+            emacs.command(alt('f')),
+            Some(Command::Move(Movement::WordRight))
+        );
+        assert_eq!(emacs.command(ctrl('s')), Some(Command::Save));
+        assert_eq!(emacs.command(ctrl('7')), Some(Command::Undo));
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(emacs.command(enter), Some(Command::Confirm));
+    }
+
+    #[test]
+    fn consecutive_kills_yank_back_as_one() {
+        let mut area = TextArea::new("one two\nthree");
+        let mut kill_ring = KillRing::default();
+        area.move_to(Pos::default(), false);
+        type_keys(
+            &mut area,
+            &mut kill_ring,
+            &[ctrl('k'), ctrl('k'), ctrl('k')],
+        );
+        assert_eq!(area.text(), "");
+        type_keys(&mut area, &mut kill_ring, &[ctrl('y')]);
+        assert_eq!(area.text(), "one two\nthree");
+
+        // Killing backwards puts the text in front of the kill before it.
+        type_keys(&mut area, &mut kill_ring, &[alt('b'), ctrl('w'), ctrl('w')]);
+        assert_eq!(area.text(), "one three");
+        type_keys(&mut area, &mut kill_ring, &[ctrl('e'), ctrl('y')]);
+        assert_eq!(area.text(), "one threetwo\n");
+    }
+
+    #[test]
+    fn yank_pop_cycles_through_earlier_kills() {
+        let mut area = TextArea::new("alpha beta gamma");
+        let mut kill_ring = KillRing::default();
+        // A movement between kills keeps them apart.
+        type_keys(
+            &mut area,
+            &mut kill_ring,
+            &[alt('b'), alt('d'), ctrl('a'), alt('d')],
+        );
+        assert_eq!(area.text(), " beta ");
+        type_keys(&mut area, &mut kill_ring, &[ctrl('y')]);
+        assert_eq!(area.text(), "alpha beta ");
+        type_keys(&mut area, &mut kill_ring, &[alt('y')]);
+        assert_eq!(area.text(), "gamma beta ");
+        type_keys(&mut area, &mut kill_ring, &[alt('y')]);
+        assert_eq!(area.text(), "alpha beta ");
+
+        // Yank-pop only follows a yank.
+        type_keys(&mut area, &mut kill_ring, &[ctrl('f'), alt('y')]);
+        assert_eq!(area.text(), "alpha beta ");
+    }
+
+    #[test]
+    fn the_mark_selects_for_copy_and_kill() {
+        let mut area = TextArea::new("copy this");
+        let mut kill_ring = KillRing::default();
+        area.move_to(Pos::default(), false);
+        type_keys(
+            &mut area,
+            &mut kill_ring,
+            &[ctrl(' '), alt('f'), alt('w'), ctrl('e'), ctrl('y')],
+        );
+        assert_eq!(area.text(), "copy thiscopy");
+        type_keys(
+            &mut area,
+            &mut kill_ring,
+            &[ctrl('a'), ctrl(' '), ctrl('f'), ctrl('f'), ctrl('w')],
+        );
+        assert_eq!(area.text(), "py thiscopy");
+    }
+}
@@ -0,0 +1,271 @@
+use crate::diary_entry::DiaryEntry;
+use chrono::{Datelike, Duration};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+
+/// The list screens, each of which remembers its own ordering.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ListScreen {
+    Main,
+    Search,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortKey {
+    #[default]
+    Oldest,
+    Newest,
+    Modified,
+    Title,
+    Length,
+    Mood,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Grouping {
+    #[default]
+    None,
+    Day,
+    Week,
+    Month,
+    Tag,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListOrder {
+    pub sort: SortKey,
+    pub grouping: Grouping,
+}
+
+pub enum ListRow<'a> {
+    Header(String),
+    Entry(&'a DiaryEntry),
+}
+
+impl SortKey {
+    pub fn next(self) -> Self {
+        match self {
+            SortKey::Oldest => SortKey::Newest,
+            SortKey::Newest => SortKey::Modified,
+            SortKey::Modified => SortKey::Title,
+            SortKey::Title => SortKey::Length,
+            SortKey::Length => SortKey::Mood,
+            SortKey::Mood => SortKey::Oldest,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortKey::Oldest => "oldest first",
+            SortKey::Newest => "newest first",
+            SortKey::Modified => "recently modified",
+            SortKey::Title => "title",
+            SortKey::Length => "longest first",
+            SortKey::Mood => "best mood first",
+        }
+    }
+}
+
+impl Grouping {
+    pub fn next(self) -> Self {
+        match self {
+            Grouping::None => Grouping::Day,
+            Grouping::Day => Grouping::Week,
+            Grouping::Week => Grouping::Month,
+            Grouping::Month => Grouping::Tag,
+            Grouping::Tag => Grouping::None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Grouping::None => "not grouped",
+            Grouping::Day => "by day",
+            Grouping::Week => "by week",
+            Grouping::Month => "by month",
+            Grouping::Tag => "by tag",
+        }
+    }
+}
+
+impl ListOrder {
+    /// Block title for a list shown in this order, e.g. `Entries (newest
+    /// first, by month)`.
+    pub fn title(&self, name: &str) -> String {
+        match self.grouping {
+            Grouping::None => format!("{} ({})", name, self.sort.label()),
+            grouping => format!("{} ({}, {})", name, self.sort.label(), grouping.label()),
+        }
+    }
+
+    /// Sorts `entries` and inserts a header before each group. Groups appear
+    /// in the order their first entry sorts in, except tag groups which are
+    /// alphabetical; an entry with several tags is listed under each of them.
+    /// Tags that differ only in case are one group, under the spelling of the
+    /// first entry listed in it.
+    pub fn arrange<'a>(&self, mut entries: Vec<&'a DiaryEntry>) -> Vec<ListRow<'a>> {
+        match self.sort {
+            SortKey::Oldest => entries.sort_by_key(|e| e.timestamp),
+            SortKey::Newest => entries.sort_by_key(|e| Reverse(e.timestamp)),
+            SortKey::Modified => {
+                entries.sort_by_key(|e| Reverse(e.modified.unwrap_or(e.timestamp)))
+            }
+            SortKey::Title => entries.sort_by_key(|e| e.title().to_lowercase()),
+            SortKey::Length => entries.sort_by_key(|e| Reverse(e.content.chars().count())),
+            // `None` sorts below every mood, so entries without one go last.
+            SortKey::Mood => entries.sort_by_key(|e| Reverse(e.mood)),
+        }
+
+        let mut groups: Vec<(String, Vec<&'a DiaryEntry>)> = Vec::new();
+        for entry in entries {
+            for key in self.group_keys(entry) {
+                match groups
+                    .iter_mut()
+                    .find(|(k, _)| k.to_lowercase() == key.to_lowercase())
+                {
+                    Some((_, members)) => members.push(entry),
+                    None => groups.push((key, vec![entry])),
+                }
+            }
+        }
+        if self.grouping == Grouping::Tag {
+            groups.sort_by_key(|(key, _)| (key == UNTAGGED, key.to_lowercase()));
+        }
+
+        let mut rows = Vec::new();
+        for (key, members) in groups {
+            if self.grouping != Grouping::None {
+                rows.push(ListRow::Header(key));
+            }
+            rows.extend(members.into_iter().map(ListRow::Entry));
+        }
+        rows
+    }
+
+    fn group_keys(&self, entry: &DiaryEntry) -> Vec<String> {
+        let date = entry.timestamp.date_naive();
+        match self.grouping {
+            Grouping::None => vec![String::new()],
+            Grouping::Day => vec![date.format("%A, %Y-%m-%d").to_string()],
+            Grouping::Week => {
+                let monday = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+                vec![format!(
+                    "Week {} ({})",
+                    date.iso_week().week(),
+                    monday.format("%Y-%m-%d")
+                )]
+            }
+            Grouping::Month => vec![date.format("%B %Y").to_string()],
+            Grouping::Tag => {
+                let mut tags: Vec<String> = entry
+                    .tags
+                    .iter()
+                    .map(|t| t.trim())
+                    .filter(|t| !t.is_empty())
+                    .map(|t| t.to_string())
+                    .collect();
+                tags.sort_by_key(|t| t.to_lowercase());
+                tags.dedup_by_key(|t| t.to_lowercase());
+                if tags.is_empty() {
+                    vec![UNTAGGED.to_string()]
+                } else {
+                    tags
+                }
+            }
+        }
+    }
+}
+
+const UNTAGGED: &str = "(untagged)";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    fn entry(id: usize, day: u32, content: &str, tags: &[&str], mood: Option<u8>) -> DiaryEntry {
+        DiaryEntry {
+            timestamp: Local.with_ymd_and_hms(2024, 3, day, 9, 0, 0).unwrap(),
+            mood,
+            ..DiaryEntry::new(
+                id,
+                content.to_string(),
+                tags.iter().map(|t| t.to_string()).collect(),
+            )
+        }
+    }
+
+    /// The rows in `order`, headers in brackets and entries by id.
+    fn arranged(entries: &[DiaryEntry], sort: SortKey, grouping: Grouping) -> Vec<String> {
+        ListOrder { sort, grouping }
+            .arrange(entries.iter().collect())
+            .into_iter()
+            .map(|row| match row {
+                ListRow::Header(header) => format!("[{}]", header),
+                ListRow::Entry(entry) => entry.id.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sorts_by_each_key() {
+        let mut entries = vec![
+            entry(1, 4, "banana split", &[], Some(2)),
+            entry(2, 1, "Apple pie, with cream", &[], None),
+            entry(3, 29, "cherry", &[], Some(5)),
+        ];
+        entries[0].modified = Some(Local.with_ymd_and_hms(2024, 4, 1, 9, 0, 0).unwrap());
+        let sorted = |sort| arranged(&entries, sort, Grouping::None);
+        assert_eq!(sorted(SortKey::Oldest), ["2", "1", "3"]);
+        assert_eq!(sorted(SortKey::Newest), ["3", "1", "2"]);
+        assert_eq!(sorted(SortKey::Modified), ["1", "3", "2"]);
+        assert_eq!(sorted(SortKey::Title), ["2", "1", "3"]);
+        assert_eq!(sorted(SortKey::Length), ["2", "1", "3"]);
+        assert_eq!(sorted(SortKey::Mood), ["3", "1", "2"]);
+    }
+
+    #[test]
+    fn groups_by_week_and_month() {
+        let entries = vec![
+            entry(1, 3, "Sunday", &[], None),
+            entry(2, 4, "Monday", &[], None),
+            entry(3, 10, "Sunday after", &[], None),
+        ];
+        assert_eq!(
+            arranged(&entries, SortKey::Oldest, Grouping::Week),
+            [
+                "[Week 9 (2024-02-26)]",
+                "1",
+                "[Week 10 (2024-03-04)]",
+                "2",
+                "3"
+            ]
+        );
+        assert_eq!(
+            arranged(&entries, SortKey::Newest, Grouping::Month),
+            ["[March 2024]", "3", "2", "1"]
+        );
+    }
+
+    #[test]
+    fn lists_entries_under_each_of_their_tags() {
+        let entries = vec![
+            entry(1, 1, "one", &["work", "Travel"], None),
+            entry(2, 2, "two", &[], None),
+            entry(3, 3, "three", &["Work", " travel ", "work"], None),
+        ];
+        assert_eq!(
+            arranged(&entries, SortKey::Oldest, Grouping::Tag),
+            [
+                "[Travel]",
+                "1",
+                "3",
+                "[work]",
+                "1",
+                "3",
+                "[(untagged)]",
+                "2"
+            ]
+        );
+    }
+}
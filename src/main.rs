@@ -1,16 +1,39 @@
+mod app;
+mod checklist;
+mod config;
+mod date_expr;
 mod diary_entry;
 mod diary_state;
-mod ui;
+mod drafts;
+mod external_editor;
+mod keymap;
+mod list_order;
+mod markdown;
+mod on_this_day;
+mod prompts;
+mod related;
+mod screens;
+mod search;
+mod stats;
+mod templates;
+mod text_area;
+mod undo;
+mod vim;
 
+use app::{App, Context, CrosstermEvents};
 use color_eyre::eyre::{eyre, Result};
+use config::Config;
 use diary_state::DiaryState;
-use ui::{Action, UI};
+use drafts::Drafts;
+use prompts::Prompts;
+use ratatui::{backend::CrosstermBackend, Terminal};
+use std::io::stdout;
 
 fn main() -> Result<()> {
     color_eyre::install()?;
 
     // let mut diary_state = DiaryState::new();
-    let mut diary_state = match DiaryState::load_from_file() {
+    let diary_state = match DiaryState::load_from_file() {
         Ok(state) => state,
         Err(e) => {
             if e.to_string().contains("No such file or directory") {
@@ -21,40 +44,58 @@ fn main() -> Result<()> {
             }
         }
     };
-    let mut ui = UI::new()?;
-
-    loop {
-        ui.display(&diary_state)?;
-
-        if let Some(action) = ui.handle_input(&diary_state)? {
-            match action {
-                Action::Write => {
-                    let entry = ui.get_new_entry()?;
-                    diary_state.add_entry(entry);
-                }
-                Action::View => {
-                    ui.view_entries(&diary_state)?;
-                }
-                Action::Edit => {
-                    if let Some(entry) = ui.select_entry_to_edit(&diary_state)? {
-                        let updated_entry = ui.edit_entry(&entry)?;
-                        diary_state.update_entry(updated_entry);
-                    }
-                }
-                Action::Delete => {
-                    if let Some(entry) = ui.select_entry_to_delete(&diary_state)? {
-                        diary_state.delete_entry(entry.id);
-                    }
-                }
-                Action::Search => {
-                    let query = ui.get_search_query()?;
-                    let results = diary_state.search_entries(&query);
-                    ui.display_search_results(&results)?;
-                }
-                Action::Quit => break,
+    let config = match Config::load_from_file() {
+        Ok(config) => config,
+        Err(e) => {
+            if e.to_string().contains("No such file or directory") {
+                Config::default()
+            } else {
+                return Err(eyre!("Failed to load config: {}", e));
+            }
+        }
+    };
+    let drafts = match Drafts::load_from_file() {
+        Ok(drafts) => drafts,
+        Err(e) => {
+            if e.to_string().contains("No such file or directory") {
+                Drafts::default()
+            } else {
+                return Err(eyre!("Failed to load drafts: {}", e));
+            }
+        }
+    };
+    let prompts = match Prompts::load_from_file() {
+        Ok(prompts) => prompts,
+        Err(e) => {
+            if e.to_string().contains("No such file or directory") {
+                Prompts::default()
+            } else {
+                return Err(eyre!("Failed to load prompts: {}", e));
+            }
+        }
+    };
+    let (templates, unreadable) = match templates::load_from_dir() {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            if e.to_string().contains("No such file or directory") {
+                (Vec::new(), Vec::new())
+            } else {
+                return Err(eyre!("Failed to load templates: {}", e));
             }
         }
+    };
+    let mut ctx = Context::new(diary_state, config, drafts, prompts, templates);
+    if !unreadable.is_empty() {
+        let names: Vec<String> = unreadable
+            .iter()
+            .filter_map(|path| path.file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .collect();
+        ctx.notify(format!("Could not read templates: {}", names.join(", ")));
     }
+    let events = CrosstermEvents::new()?;
+    let terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
+    App::new(terminal, Box::new(events), ctx).run()?;
 
     Ok(())
 }
@@ -0,0 +1,688 @@
+use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use regex::Regex;
+use std::ops::Range;
+use std::sync::OnceLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+const CODE_THEME: &str = "base16-ocean.dark";
+
+/// Renders an entry's Markdown as styled lines. Line breaks inside a
+/// paragraph are kept, since entries are usually written line by line.
+pub fn render(text: &str) -> Vec<Line<'static>> {
+    render_mapped(text).lines
+}
+
+/// An entry rendered by [`render_mapped`].
+#[derive(Default)]
+pub struct Rendered {
+    pub lines: Vec<Line<'static>>,
+    /// The line each checkbox is shown on, in the order
+    /// [`checklist::tasks`](crate::checklist::tasks) lists them.
+    pub task_lines: Vec<usize>,
+    /// The line of the source each line shown comes from.
+    pub source_lines: Vec<usize>,
+}
+
+/// Like [`render`], also mapping what is shown back to the source.
+pub fn render_mapped(text: &str) -> Rendered {
+    let options = Options::ENABLE_TASKLISTS | Options::ENABLE_STRIKETHROUGH;
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let mut renderer = Renderer::default();
+    for (event, range) in Parser::new_ext(text, options).into_offset_iter() {
+        renderer.source_line = line_starts.partition_point(|&start| start <= range.start) - 1;
+        renderer.event(event);
+    }
+    renderer.flush_line();
+    if renderer.separated {
+        renderer.pop_line();
+    }
+    Rendered {
+        lines: renderer.lines,
+        task_lines: renderer.task_lines,
+        source_lines: renderer.source_lines,
+    }
+}
+
+#[derive(Default)]
+struct Renderer {
+    lines: Vec<Line<'static>>,
+    /// The line being built, without its prefix.
+    spans: Vec<Span<'static>>,
+    styles: Vec<Style>,
+    quote_depth: usize,
+    /// The next number of each open list, `None` for bulleted ones.
+    lists: Vec<Option<u64>>,
+    /// The marker width of each open item, which its later lines indent by.
+    items: Vec<usize>,
+    /// Whether each open item is a done task, shown dimmed.
+    done: Vec<bool>,
+    /// The marker still to be shown on the first line of the current item.
+    marker: Option<Span<'static>>,
+    /// The language and text of the code block being read.
+    code: Option<(String, String)>,
+    /// The source line the code block's text starts on.
+    code_line: usize,
+    links: Vec<(String, usize)>,
+    /// Whether the last line is an empty one between blocks.
+    separated: bool,
+    /// Whether the next line shown starts with a checkbox.
+    task_pending: bool,
+    task_lines: Vec<usize>,
+    /// The source line of the event being read.
+    source_line: usize,
+    /// The source line of the line being built, if it isn't that one.
+    line_source: Option<usize>,
+    source_lines: Vec<usize>,
+}
+
+impl Renderer {
+    fn style(&self) -> Style {
+        self.styles.last().copied().unwrap_or_default()
+    }
+
+    fn push_style(&mut self, style: Style) {
+        self.styles.push(self.style().patch(style));
+    }
+
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => match &mut self.code {
+                Some((_, code)) => code.push_str(&text),
+                None => self.text(&text, self.style()),
+            },
+            Event::Code(code) => {
+                let style = self.style().fg(Color::LightYellow);
+                self.text(&code, style);
+            }
+            Event::Html(html) | Event::InlineHtml(html) => {
+                for (i, line) in html.lines().enumerate() {
+                    if i > 0 {
+                        self.flush_line();
+                        self.line_source = Some(self.source_line + i);
+                    }
+                    self.text(line, self.style());
+                }
+            }
+            Event::SoftBreak | Event::HardBreak => self.flush_line(),
+            Event::Rule => {
+                self.flush_line();
+                self.spans.push(Span::styled(
+                    "─".repeat(40),
+                    Style::default().fg(Color::DarkGray),
+                ));
+                self.flush_line();
+                self.blank_line();
+            }
+            Event::TaskListMarker(checked) => {
+                let (text, color) = if checked {
+                    ("[x] ", Color::Green)
+                } else {
+                    ("[ ] ", Color::Yellow)
+                };
+                self.marker = Some(Span::styled(text, Style::default().fg(color)));
+                self.task_pending = true;
+                if let Some(width) = self.items.last_mut() {
+                    *width = text.len();
+                }
+                if checked {
+                    self.push_style(Style::default().fg(Color::DarkGray));
+                    if let Some(done) = self.done.last_mut() {
+                        *done = true;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Heading { level, .. } => {
+                self.flush_line();
+                let style = match level {
+                    HeadingLevel::H1 => Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                    HeadingLevel::H2 => Style::default()
+                        .fg(Color::Cyan)
+                        .add_modifier(Modifier::BOLD),
+                    _ => Style::default().add_modifier(Modifier::BOLD),
+                };
+                self.push_style(style);
+            }
+            Tag::BlockQuote(_) => {
+                self.flush_line();
+                self.quote_depth += 1;
+                self.push_style(Style::default().add_modifier(Modifier::ITALIC));
+            }
+            Tag::CodeBlock(kind) => {
+                self.flush_line();
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => {
+                        self.code_line = self.source_line + 1;
+                        info.split_whitespace().next().unwrap_or("").to_string()
+                    }
+                    CodeBlockKind::Indented => {
+                        self.code_line = self.source_line;
+                        String::new()
+                    }
+                };
+                self.code = Some((language, String::new()));
+            }
+            Tag::List(start) => {
+                self.flush_line();
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.flush_line();
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => "• ".to_string(),
+                };
+                self.items.push(marker.chars().count());
+                self.done.push(false);
+                self.marker = Some(Span::styled(marker, Style::default().fg(Color::Yellow)));
+            }
+            Tag::Emphasis => self.push_style(Style::default().add_modifier(Modifier::ITALIC)),
+            Tag::Strong => self.push_style(Style::default().add_modifier(Modifier::BOLD)),
+            Tag::Strikethrough => {
+                self.push_style(Style::default().add_modifier(Modifier::CROSSED_OUT))
+            }
+            Tag::Link { dest_url, .. } => {
+                self.links.push((dest_url.to_string(), self.spans.len()));
+                self.push_style(
+                    Style::default()
+                        .fg(Color::Blue)
+                        .add_modifier(Modifier::UNDERLINED),
+                );
+            }
+            Tag::Image { dest_url, .. } => {
+                self.links.push((dest_url.to_string(), self.spans.len()));
+                self.push_style(Style::default().fg(Color::Magenta));
+                self.text("[image] ", self.style());
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph => {
+                self.flush_line();
+                if self.items.is_empty() {
+                    self.blank_line();
+                }
+            }
+            TagEnd::Heading(_) => {
+                self.styles.pop();
+                self.flush_line();
+                self.blank_line();
+            }
+            TagEnd::BlockQuote(_) => {
+                self.flush_line();
+                self.styles.pop();
+                self.quote_depth -= 1;
+                // The quote's last paragraph left a barred empty line.
+                if self.separated {
+                    self.pop_line();
+                    self.separated = false;
+                }
+                self.blank_line();
+            }
+            TagEnd::CodeBlock => {
+                if let Some((language, code)) = self.code.take() {
+                    for (i, line) in highlight(&language, &code).into_iter().enumerate() {
+                        self.line_source = Some(self.code_line + i);
+                        self.spans.push(Span::raw("  "));
+                        self.spans.extend(line);
+                        self.flush_line();
+                    }
+                }
+                if self.items.is_empty() {
+                    self.blank_line();
+                }
+            }
+            TagEnd::List(_) => {
+                self.flush_line();
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.blank_line();
+                }
+            }
+            TagEnd::Item => {
+                // An empty item still shows its marker.
+                if self.marker.is_some() && self.spans.is_empty() {
+                    self.spans.push(Span::raw(""));
+                }
+                self.flush_line();
+                self.items.pop();
+                if self.done.pop() == Some(true) {
+                    self.styles.pop();
+                }
+                self.marker = None;
+            }
+            TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough => {
+                self.styles.pop();
+            }
+            TagEnd::Link | TagEnd::Image => {
+                self.styles.pop();
+                if let Some((url, first)) = self.links.pop() {
+                    // Show where the link goes unless its text already says.
+                    let text: String = self
+                        .spans
+                        .get(first..)
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|s| s.content.as_ref())
+                        .collect();
+                    if !url.is_empty() && text.trim_start_matches("[image] ") != url {
+                        self.text(&format!(" <{}>", url), Style::default().fg(Color::DarkGray));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, text: &str, style: Style) {
+        if self.spans.is_empty() {
+            self.line_source.get_or_insert(self.source_line);
+        }
+        self.spans.push(Span::styled(text.to_string(), style));
+    }
+
+    /// Ends the current line, if anything is on it, adding the quote bars,
+    /// list indent and item marker in front.
+    fn flush_line(&mut self) {
+        if self.spans.is_empty() {
+            return;
+        }
+        if self.task_pending {
+            self.task_lines.push(self.lines.len());
+            self.task_pending = false;
+        }
+        let mut line = self.prefix();
+        line.append(&mut self.spans);
+        self.lines.push(Line::from(line));
+        let source = self.line_source.take().unwrap_or(self.source_line);
+        self.source_lines.push(source);
+        self.separated = false;
+    }
+
+    fn pop_line(&mut self) {
+        self.lines.pop();
+        self.source_lines.pop();
+    }
+
+    /// Separates blocks with an empty line, without doubling one up.
+    fn blank_line(&mut self) {
+        if !self.separated && !self.lines.is_empty() {
+            let quote = self.quote_prefix();
+            self.lines.push(Line::from(quote));
+            // It belongs with the line above, wherever the block ending
+            // started.
+            let source = self.source_lines.last().copied().unwrap_or_default();
+            self.source_lines.push(source);
+            self.separated = true;
+        }
+    }
+
+    fn quote_prefix(&self) -> Vec<Span<'static>> {
+        (0..self.quote_depth)
+            .map(|_| Span::styled("│ ", Style::default().fg(Color::DarkGray)))
+            .collect()
+    }
+
+    fn prefix(&mut self) -> Vec<Span<'static>> {
+        let mut prefix = self.quote_prefix();
+        let marker = self.marker.take();
+        let outer = if marker.is_some() {
+            self.items.len().saturating_sub(1)
+        } else {
+            self.items.len()
+        };
+        let indent: usize = self.items[..outer].iter().sum();
+        if indent > 0 {
+            prefix.push(Span::raw(" ".repeat(indent)));
+        }
+        prefix.extend(marker);
+        prefix
+    }
+}
+
+/// Highlights a code block with syntect, falling back to plain text for an
+/// unknown language.
+fn highlight(language: &str, code: &str) -> Vec<Vec<Span<'static>>> {
+    static ASSETS: OnceLock<(SyntaxSet, Theme)> = OnceLock::new();
+    let (syntaxes, theme) = ASSETS.get_or_init(|| {
+        let mut themes = ThemeSet::load_defaults();
+        let theme = themes.themes.remove(CODE_THEME).unwrap_or_default();
+        (SyntaxSet::load_defaults_newlines(), theme)
+    });
+    let syntax = syntaxes
+        .find_syntax_by_token(language)
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+    let mut highlighter = HighlightLines::new(syntax, theme);
+
+    LinesWithEndings::from(code)
+        .map(|line| match highlighter.highlight_line(line, syntaxes) {
+            Ok(ranges) => ranges
+                .into_iter()
+                .map(|(style, text)| {
+                    let color = style.foreground;
+                    Span::styled(
+                        text.trim_end_matches('\n').to_string(),
+                        Style::default().fg(Color::Rgb(color.r, color.g, color.b)),
+                    )
+                })
+                .collect(),
+            Err(_) => vec![Span::raw(line.trim_end_matches('\n').to_string())],
+        })
+        .collect()
+}
+
+/// A styled byte range of a line.
+pub type Run = (Range<usize>, Style);
+
+/// Highlights Markdown source line by line for the editor: headings, block
+/// quotes, list markers and checkboxes, emphasis, code, links, `[[links]]`
+/// and `#tags`. Fenced code is tracked across lines.
+pub fn highlight_source(lines: &[String]) -> Vec<Vec<Run>> {
+    static PATTERNS: OnceLock<SourcePatterns> = OnceLock::new();
+    let patterns = PATTERNS.get_or_init(SourcePatterns::new);
+
+    let mut in_fence = false;
+    lines
+        .iter()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_fence = !in_fence;
+                return vec![(0..line.len(), Style::default().fg(Color::DarkGray))];
+            }
+            if in_fence {
+                return vec![(0..line.len(), Style::default().fg(Color::LightYellow))];
+            }
+            patterns.line_runs(line)
+        })
+        .collect()
+}
+
+struct SourcePatterns {
+    heading: Regex,
+    quote: Regex,
+    list: Regex,
+    code: Regex,
+    strong: Regex,
+    emphasis: Regex,
+    wiki_link: Regex,
+    link: Regex,
+    tag: Regex,
+}
+
+impl SourcePatterns {
+    fn new() -> Self {
+        let regex = |pattern| Regex::new(pattern).unwrap();
+        SourcePatterns {
+            heading: regex(r"^ {0,3}#{1,6}(\s|$)"),
+            quote: regex(r"^\s*(>\s?)+"),
+            list: regex(r"^(\s*(?:[-*+]|\d+[.)])\s+)(\[[ xX]\]\s)?"),
+            code: regex(r"`[^`]+`"),
+            strong: regex(r"\*\*[^*]+\*\*|__[^_]+__"),
+            emphasis: regex(r"\*[^*\s](?:[^*]*[^*\s])?\*|\b_[^_]+_\b"),
+            wiki_link: regex(r"\[\[[^\]]+\]\]"),
+            link: regex(r"!?\[[^\]]*\]\([^)]*\)"),
+            tag: regex(r"(?:^|\s)(#[\w-]+)"),
+        }
+    }
+
+    fn line_runs(&self, line: &str) -> Vec<Run> {
+        let mut runs = Vec::new();
+        if self.heading.is_match(line) {
+            let style = Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD);
+            runs.push((0..line.len(), style));
+        } else if let Some(quote) = self.quote.find(line) {
+            runs.push((
+                0..line.len(),
+                Style::default().add_modifier(Modifier::ITALIC),
+            ));
+            runs.push((quote.range(), Style::default().fg(Color::DarkGray)));
+        } else if let Some(list) = self.list.captures(line) {
+            let marker = &list[1];
+            let indent = marker.len() - marker.trim_start().len();
+            runs.push((indent..marker.len(), Style::default().fg(Color::Yellow)));
+            if let Some(checkbox) = list.get(2) {
+                let color = if checkbox.as_str().starts_with("[ ]") {
+                    Color::Yellow
+                } else {
+                    Color::Green
+                };
+                runs.push((checkbox.range(), Style::default().fg(color)));
+            }
+        }
+
+        // Nothing inside inline code is Markdown, and emphasis inside strong
+        // text is its own asterisks.
+        let code: Vec<Range<usize>> = self.code.find_iter(line).map(|m| m.range()).collect();
+        let outside_code = |range: &Range<usize>| {
+            !code
+                .iter()
+                .any(|c| c.start < range.end && range.start < c.end)
+        };
+        let strong: Vec<Range<usize>> = self
+            .strong
+            .find_iter(line)
+            .map(|m| m.range())
+            .filter(outside_code)
+            .collect();
+
+        let link_style = Style::default()
+            .fg(Color::Blue)
+            .add_modifier(Modifier::UNDERLINED);
+        let inline = [
+            (&self.wiki_link, link_style),
+            (&self.link, link_style),
+            (&self.tag, Style::default().fg(Color::Magenta)),
+        ];
+        for (pattern, style) in inline {
+            for captures in pattern.captures_iter(line) {
+                let found = captures.get(1).or(captures.get(0)).unwrap();
+                if outside_code(&found.range()) {
+                    runs.push((found.range(), style));
+                }
+            }
+        }
+        for found in self.emphasis.find_iter(line) {
+            let range = found.range();
+            let in_strong = strong
+                .iter()
+                .any(|s| s.start < range.end && range.start < s.end);
+            if outside_code(&range) && !in_strong {
+                runs.push((range, Style::default().add_modifier(Modifier::ITALIC)));
+            }
+        }
+        runs.extend(
+            strong
+                .into_iter()
+                .map(|range| (range, Style::default().add_modifier(Modifier::BOLD))),
+        );
+        runs.extend(
+            code.into_iter()
+                .map(|range| (range, Style::default().fg(Color::LightYellow))),
+        );
+        runs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(lines: &[Line]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    /// The style of the span showing exactly `text`.
+    fn style_of(lines: &[Line], text: &str) -> Style {
+        lines
+            .iter()
+            .flat_map(|line| &line.spans)
+            .find(|span| span.content == text)
+            .unwrap_or_else(|| panic!("no span {:?}", text))
+            .style
+    }
+
+    /// The styles `runs` give the part of `line` showing `text`.
+    fn runs_on<'a>(runs: &'a [Run], line: &str, text: &str) -> Vec<&'a Style> {
+        let start = line.find(text).unwrap();
+        let range = start..start + text.len();
+        runs.iter()
+            .filter(|(r, _)| *r == range)
+            .map(|(_, style)| style)
+            .collect()
+    }
+
+    #[test]
+    fn renders_headings_and_emphasis() {
+        let lines = render("# Title\n## Part\n\nSome *soft* and **bold** ~~gone~~\ntext");
+        assert_eq!(
+            texts(&lines),
+            ["Title", "", "Part", "", "Some soft and bold gone", "text"]
+        );
+        let heading = Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD);
+        assert_eq!(
+            style_of(&lines, "Title"),
+            heading.add_modifier(Modifier::UNDERLINED)
+        );
+        assert_eq!(style_of(&lines, "Part"), heading);
+        assert_eq!(style_of(&lines, "soft").add_modifier, Modifier::ITALIC);
+        assert_eq!(style_of(&lines, "bold").add_modifier, Modifier::BOLD);
+        assert_eq!(style_of(&lines, "gone").add_modifier, Modifier::CROSSED_OUT);
+    }
+
+    #[test]
+    fn renders_lists_and_finds_the_task_lines() {
+        let text = "Intro\n\n- one\n- [ ] todo\n  more\n* [x] done\n\n1. first\n2. second";
+        let Rendered {
+            lines,
+            task_lines,
+            source_lines,
+        } = render_mapped(text);
+        assert_eq!(
+            texts(&lines),
+            [
+                "Intro",
+                "",
+                "• one",
+                "[ ] todo",
+                "    more",
+                // A different bullet starts another list.
+                "",
+                "[x] done",
+                "",
+                "1. first",
+                "2. second"
+            ]
+        );
+        assert_eq!(task_lines, [3, 6]);
+        assert_eq!(task_lines.len(), crate::checklist::tasks(text).len());
+        assert_eq!(source_lines, [0, 0, 2, 3, 4, 4, 5, 5, 7, 8]);
+        assert_eq!(style_of(&lines, "[ ] ").fg, Some(Color::Yellow));
+        assert_eq!(style_of(&lines, "[x] ").fg, Some(Color::Green));
+        assert_eq!(style_of(&lines, "done").fg, Some(Color::DarkGray));
+    }
+
+    #[test]
+    fn highlights_fenced_code_in_known_languages() {
+        let rendered =
+            render_mapped("```rust\nfn main() {}\n```\n\n```nosuchlang\nfn plain() {}\n```");
+        let lines = rendered.lines;
+        assert_eq!(texts(&lines), ["  fn main() {}", "", "  fn plain() {}"]);
+        assert_eq!(rendered.source_lines[0], 1);
+        assert_eq!(rendered.source_lines[2], 5);
+        let colors = |line: &Line| {
+            let mut colors: Vec<_> = line.spans[1..].iter().map(|s| s.style.fg).collect();
+            colors.dedup();
+            colors.len()
+        };
+        assert!(colors(&lines[0]) > 1);
+        assert_eq!(colors(&lines[2]), 1, "an unknown language is plain text");
+    }
+
+    #[test]
+    fn shows_where_links_go() {
+        let lines = render("[site](https://example.com) and <https://example.com>");
+        assert_eq!(
+            texts(&lines),
+            ["site <https://example.com> and https://example.com"]
+        );
+        let link = Style::default()
+            .fg(Color::Blue)
+            .add_modifier(Modifier::UNDERLINED);
+        assert_eq!(style_of(&lines, "site"), link);
+        assert_eq!(style_of(&lines, "https://example.com"), link);
+    }
+
+    #[test]
+    fn highlights_the_source_line_by_line() {
+        let lines: Vec<String> = [
+            "# Heading",
+            "See #tag, [[Other entry]] and `#code`",
+            "```",
+            "# not a heading",
+            "```",
+            "- [x] **done**",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+        let runs = highlight_source(&lines);
+
+        assert_eq!(
+            runs_on(&runs[0], &lines[0], "# Heading")[0].fg,
+            Some(Color::Cyan)
+        );
+        let line = &lines[1];
+        assert_eq!(runs_on(&runs[1], line, "#tag")[0].fg, Some(Color::Magenta));
+        assert_eq!(
+            runs_on(&runs[1], line, "[[Other entry]]")[0].fg,
+            Some(Color::Blue)
+        );
+        assert!(
+            runs_on(&runs[1], line, "#code").is_empty(),
+            "not inside code"
+        );
+        assert_eq!(
+            runs_on(&runs[1], line, "`#code`")[0].fg,
+            Some(Color::LightYellow)
+        );
+        assert_eq!(
+            runs[3],
+            [(0..lines[3].len(), Style::default().fg(Color::LightYellow))]
+        );
+        assert_eq!(
+            runs_on(&runs[5], &lines[5], "[x] ")[0].fg,
+            Some(Color::Green)
+        );
+        assert_eq!(
+            runs_on(&runs[5], &lines[5], "**done**")[0].add_modifier,
+            Modifier::BOLD
+        );
+    }
+}
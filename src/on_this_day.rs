@@ -0,0 +1,114 @@
+//! Finding the entries written on today's date in earlier years, and
+//! optionally a week and a month ago, to look back on.
+
+use crate::diary_entry::DiaryEntry;
+use chrono::{Datelike, Days, Months, NaiveDate};
+use std::cmp::Reverse;
+
+/// An entry from an earlier day, with how long ago that was.
+pub struct Memory<'a> {
+    pub when: String,
+    pub entry: &'a DiaryEntry,
+}
+
+/// The entries written on this day of the year in earlier years and, if
+/// `recent`, exactly a week and a month ago, most recent first. A leap day
+/// is remembered on the 28th of February in other years.
+pub fn memories(entries: &[DiaryEntry], today: NaiveDate, recent: bool) -> Vec<Memory<'_>> {
+    let week_ago = today - Days::new(7);
+    let month_ago = today - Months::new(1);
+    let mut memories: Vec<Memory> = entries
+        .iter()
+        .filter_map(|entry| {
+            let day = entry.timestamp.date_naive();
+            let when = if recent && day == week_ago {
+                "A week ago".to_string()
+            } else if recent && day == month_ago {
+                "A month ago".to_string()
+            } else if day.year() < today.year() && same_day_of_year(day, today) {
+                match today.year() - day.year() {
+                    1 => "1 year ago".to_string(),
+                    years => format!("{} years ago", years),
+                }
+            } else {
+                return None;
+            };
+            Some(Memory { when, entry })
+        })
+        .collect();
+    memories.sort_by_key(|m| Reverse(m.entry.timestamp));
+    memories
+}
+
+fn same_day_of_year(day: NaiveDate, today: NaiveDate) -> bool {
+    let leap_day_on_28th = day.month() == 2
+        && day.day() == 29
+        && today.month() == 2
+        && today.day() == 28
+        && today.with_day(29).is_none();
+    (day.month(), day.day()) == (today.month(), today.day()) || leap_day_on_28th
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn entries(days: &[NaiveDate]) -> Vec<DiaryEntry> {
+        days.iter()
+            .map(|day| DiaryEntry {
+                timestamp: Local
+                    .from_local_datetime(&day.and_hms_opt(9, 0, 0).unwrap())
+                    .unwrap(),
+                ..DiaryEntry::new(0, day.to_string(), Vec::new())
+            })
+            .collect()
+    }
+
+    fn found(entries: &[DiaryEntry], today: NaiveDate, recent: bool) -> Vec<(String, String)> {
+        memories(entries, today, recent)
+            .into_iter()
+            .map(|m| (m.when, m.entry.content.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn finds_this_day_in_earlier_years() {
+        let entries = entries(&[
+            date(2021, 5, 10),
+            date(2023, 5, 10),
+            date(2023, 5, 11),
+            date(2024, 5, 10),
+            date(2024, 5, 3),
+            date(2024, 4, 10),
+        ]);
+        assert_eq!(
+            found(&entries, date(2024, 5, 10), false),
+            [
+                ("1 year ago".to_string(), "2023-05-10".to_string()),
+                ("3 years ago".to_string(), "2021-05-10".to_string()),
+            ]
+        );
+        assert_eq!(
+            found(&entries, date(2024, 5, 10), true),
+            [
+                ("A week ago".to_string(), "2024-05-03".to_string()),
+                ("A month ago".to_string(), "2024-04-10".to_string()),
+                ("1 year ago".to_string(), "2023-05-10".to_string()),
+                ("3 years ago".to_string(), "2021-05-10".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn remembers_leap_days_on_the_28th() {
+        let entries = entries(&[date(2020, 2, 29), date(2020, 2, 28)]);
+        assert_eq!(found(&entries, date(2021, 2, 28), false).len(), 2);
+        assert_eq!(found(&entries, date(2024, 2, 28), false).len(), 1);
+        assert_eq!(found(&entries, date(2024, 2, 29), false).len(), 1);
+    }
+}
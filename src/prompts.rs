@@ -0,0 +1,140 @@
+use chrono::{Datelike, NaiveDate};
+use color_eyre::Result;
+use serde::Deserialize;
+use std::fs;
+
+/// The prompts that come with the diary, as category and question.
+const BUILT_IN: [(&str, &str); 15] = [
+    (
+        "gratitude",
+        "What are three things you're grateful for today?",
+    ),
+    ("gratitude", "Who made your day a little better, and how?"),
+    (
+        "gratitude",
+        "What's something you take for granted that you'd miss?",
+    ),
+    ("gratitude", "What small pleasure did you enjoy today?"),
+    ("reflection", "What's on your mind right now?"),
+    ("reflection", "What did you learn today?"),
+    ("reflection", "What would you tell yourself a year ago?"),
+    (
+        "reflection",
+        "When did you feel most like yourself this week?",
+    ),
+    ("reflection", "What are you avoiding, and why?"),
+    ("reflection", "What do you want to remember about today?"),
+    ("retro", "What went well?"),
+    ("retro", "What didn't go well?"),
+    ("retro", "What will you do differently tomorrow?"),
+    ("retro", "What are you looking forward to?"),
+    ("retro", "What's one thing you could let go of?"),
+];
+
+/// A question to write an entry about.
+#[derive(Clone, Deserialize)]
+pub struct Prompt {
+    #[serde(default = "custom_category")]
+    pub category: String,
+    pub text: String,
+}
+
+fn custom_category() -> String {
+    "custom".to_string()
+}
+
+/// The built-in prompts, followed by any from `diary_prompts.json`.
+pub struct Prompts {
+    prompts: Vec<Prompt>,
+}
+
+impl Default for Prompts {
+    fn default() -> Self {
+        let prompts = BUILT_IN
+            .iter()
+            .map(|(category, text)| Prompt {
+                category: category.to_string(),
+                text: text.to_string(),
+            })
+            .collect();
+        Prompts { prompts }
+    }
+}
+
+impl Prompts {
+    /// The built-in prompts and the user's own, which `diary_prompts.json`
+    /// lists as `{"category": "...", "text": "..."}` objects; the category
+    /// may be left out.
+    pub fn load_from_file() -> Result<Self> {
+        let serialized = fs::read_to_string("diary_prompts.json")?;
+        let own: Vec<Prompt> = serde_json::from_str(&serialized)?;
+        let mut prompts = Prompts::default();
+        prompts.prompts.extend(own);
+        Ok(prompts)
+    }
+
+    pub fn get_prompts(&self) -> &Vec<Prompt> {
+        &self.prompts
+    }
+
+    /// The prompt for `today`, which goes round the library a day at a time.
+    pub fn of_the_day(&self, today: NaiveDate) -> Option<&Prompt> {
+        let day = today.num_days_from_ce().unsigned_abs() as usize;
+        self.prompts.get(day % self.prompts.len().max(1))
+    }
+
+    /// The prompt after the one reading `text`, going once round the library
+    /// from the one reading `start`, or from the first, and then to `None`,
+    /// so stepping through them also passes by having no prompt.
+    pub fn after(&self, text: Option<&str>, start: Option<&str>) -> Option<&Prompt> {
+        let position = |text| self.prompts.iter().position(|p| p.text == text);
+        let start = start.and_then(position).unwrap_or(0);
+        let next = match text {
+            Some(text) => (position(text)? + 1) % self.prompts.len(),
+            None => start,
+        };
+        if text.is_some() && next == start {
+            return None;
+        }
+        self.prompts.get(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(prompts: &Prompts, start: Option<&str>) -> Vec<Option<String>> {
+        let mut current = None;
+        let mut seen = Vec::new();
+        for _ in 0..=BUILT_IN.len() {
+            current = prompts
+                .after(current.as_deref(), start)
+                .map(|p| p.text.clone());
+            seen.push(current.clone());
+        }
+        seen
+    }
+
+    #[test]
+    fn steps_once_round_the_library_from_the_start() {
+        let prompts = Prompts::default();
+        let text = |i: usize| Some(BUILT_IN[i].1.to_string());
+
+        let from_first = texts(&prompts, None);
+        assert_eq!(from_first[0], text(0));
+        assert_eq!(from_first[BUILT_IN.len() - 1], text(BUILT_IN.len() - 1));
+        assert_eq!(from_first[BUILT_IN.len()], None);
+
+        let from_third = texts(&prompts, BUILT_IN[2].1.into());
+        assert_eq!(from_third[0], text(2));
+        assert_eq!(from_third[BUILT_IN.len() - 3], text(BUILT_IN.len() - 1));
+        assert_eq!(from_third[BUILT_IN.len() - 2], text(0));
+        assert_eq!(from_third[BUILT_IN.len() - 1], text(1));
+        assert_eq!(from_third[BUILT_IN.len()], None);
+
+        assert!(prompts
+            .after(Some("A prompt since removed"), None)
+            .is_none());
+    }
+}
@@ -0,0 +1,232 @@
+use crate::diary_entry::DiaryEntry;
+use std::collections::{HashMap, HashSet};
+
+/// How much of the similarity score comes from the text; the rest comes from
+/// shared tags.
+const CONTENT_WEIGHT: f64 = 0.8;
+const TAG_WEIGHT: f64 = 1.0 - CONTENT_WEIGHT;
+
+/// Words too common to say anything about what an entry is about, in the
+/// languages the diary is usually written in.
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "that", "this", "with", "was", "are", "but", "not", "you", "have", "had",
+    "has", "just", "from", "they", "she", "his", "her", "him", "its", "our", "out", "all", "can",
+    "will", "been", "were", "what", "when", "then", "than", "there", "about", "into", "some",
+    "yang", "dan", "dari", "ini", "itu", "untuk", "dengan", "tidak", "ada", "saya", "aku", "akan",
+    "juga", "pada", "sudah", "karena", "bisa", "atau", "jadi", "tapi",
+];
+
+struct Document {
+    term_counts: HashMap<String, usize>,
+    tags: HashSet<String>,
+}
+
+/// Finds entries similar to a given one, using TF-IDF cosine similarity over
+/// the content combined with tag overlap.
+///
+/// Only raw term counts are stored per entry and the weights are derived when
+/// querying, so adding, editing or removing an entry only touches that entry's
+/// counts and the shared document frequencies.
+#[derive(Default)]
+pub struct RelatedIndex {
+    documents: HashMap<usize, Document>,
+    document_frequency: HashMap<String, usize>,
+}
+
+impl RelatedIndex {
+    pub fn build(entries: &[DiaryEntry]) -> Self {
+        let mut index = RelatedIndex::default();
+        for entry in entries {
+            index.upsert(entry);
+        }
+        index
+    }
+
+    /// Adds `entry` to the index, replacing any previous version of it.
+    pub fn upsert(&mut self, entry: &DiaryEntry) {
+        self.remove(entry.id);
+
+        let mut term_counts = HashMap::new();
+        for term in tokenize(&entry.content) {
+            *term_counts.entry(term).or_insert(0) += 1;
+        }
+        for term in term_counts.keys() {
+            *self.document_frequency.entry(term.clone()).or_insert(0) += 1;
+        }
+
+        let tags = entry
+            .tags
+            .iter()
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !t.is_empty())
+            .collect();
+
+        self.documents
+            .insert(entry.id, Document { term_counts, tags });
+    }
+
+    pub fn remove(&mut self, id: usize) {
+        if let Some(document) = self.documents.remove(&id) {
+            for term in document.term_counts.keys() {
+                if let Some(count) = self.document_frequency.get_mut(term) {
+                    *count -= 1;
+                    if *count == 0 {
+                        self.document_frequency.remove(term);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Up to `limit` other entries most similar to entry `id`, with their
+    /// similarity in `0.0..=1.0`, best first. Unrelated entries are left out.
+    pub fn related(&self, id: usize, limit: usize) -> Vec<(usize, f64)> {
+        let Some(document) = self.documents.get(&id) else {
+            return Vec::new();
+        };
+        let vector = self.weights(document);
+
+        let mut scores: Vec<(usize, f64)> = self
+            .documents
+            .iter()
+            .filter(|(other_id, _)| **other_id != id)
+            .map(|(other_id, other)| {
+                let content = cosine(&vector, &self.weights(other));
+                let tags = jaccard(&document.tags, &other.tags);
+                (*other_id, CONTENT_WEIGHT * content + TAG_WEIGHT * tags)
+            })
+            .filter(|(_, score)| *score > 0.0)
+            .collect();
+
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        scores.truncate(limit);
+        scores
+    }
+
+    fn weights<'a>(&self, document: &'a Document) -> HashMap<&'a str, f64> {
+        let total_documents = self.documents.len() as f64;
+        document
+            .term_counts
+            .iter()
+            .map(|(term, count)| {
+                let frequency = self.document_frequency.get(term).copied().unwrap_or(0) as f64;
+                let idf = ((total_documents + 1.0) / (frequency + 1.0)).ln() + 1.0;
+                (term.as_str(), *count as f64 * idf)
+            })
+            .collect()
+    }
+}
+
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(|word| word.to_lowercase())
+        .filter(|word| word.chars().count() > 2 && !STOP_WORDS.contains(&word.as_str()))
+}
+
+fn cosine(a: &HashMap<&str, f64>, b: &HashMap<&str, f64>) -> f64 {
+    let dot: f64 = a
+        .iter()
+        .filter_map(|(term, weight)| b.get(term).map(|other| weight * other))
+        .sum();
+    if dot == 0.0 {
+        return 0.0;
+    }
+    let norm = |v: &HashMap<&str, f64>| v.values().map(|w| w * w).sum::<f64>().sqrt();
+    dot / (norm(a) * norm(b))
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: usize, content: &str, tags: &[&str]) -> DiaryEntry {
+        DiaryEntry::new(
+            id,
+            content.to_string(),
+            tags.iter().map(|t| t.to_string()).collect(),
+        )
+    }
+
+    fn entries() -> Vec<DiaryEntry> {
+        vec![
+            entry(1, "Walked the dog along the river", &["pets"]),
+            entry(2, "The dog chased ducks by the river", &["pets"]),
+            entry(3, "Took the dog to the vet", &[]),
+            entry(4, "Baked bread for the neighbours", &["Food"]),
+            entry(5, "Fresh bread and soup", &["food "]),
+        ]
+    }
+
+    fn ids(related: Vec<(usize, f64)>) -> Vec<usize> {
+        related.into_iter().map(|(id, _)| id).collect()
+    }
+
+    #[test]
+    fn ranks_by_shared_words_and_tags() {
+        let index = RelatedIndex::build(&entries());
+        let related = index.related(1, 10);
+        assert_eq!(
+            ids(related.clone()),
+            [2, 3],
+            "unrelated entries are left out"
+        );
+        assert!(related.iter().all(|(_, score)| (0.0..=1.0).contains(score)));
+        assert!(related[0].1 > related[1].1);
+
+        // Tags match whatever their case and spacing.
+        assert_eq!(ids(index.related(4, 10)), [5]);
+        assert_eq!(ids(index.related(1, 1)), [2]);
+    }
+
+    #[test]
+    fn leaves_out_the_entry_itself() {
+        let index = RelatedIndex::build(&[
+            entry(1, "Same words", &["same"]),
+            entry(2, "Same words", &["same"]),
+        ]);
+        let related = index.related(1, 10);
+        assert_eq!(ids(related.clone()), [2]);
+        assert!((related[0].1 - 1.0).abs() < 1e-9);
+        assert!(index.related(3, 10).is_empty());
+    }
+
+    #[test]
+    fn updates_match_a_fresh_build() {
+        let mut entries = entries();
+        let mut index = RelatedIndex::build(&entries);
+        entries[2] = entry(3, "Baked more bread", &["food"]);
+        index.upsert(&entries[2]);
+        entries.remove(0);
+        index.remove(1);
+        index.upsert(&entry(6, "The river was high", &[]));
+        entries.push(entry(6, "The river was high", &[]));
+
+        let fresh = RelatedIndex::build(&entries);
+        assert_eq!(index.document_frequency, fresh.document_frequency);
+        for entry in &entries {
+            assert_eq!(index.related(entry.id, 10), fresh.related(entry.id, 10));
+        }
+        assert!(index.related(1, 10).is_empty());
+    }
+
+    #[test]
+    fn measures_overlap() {
+        let tags = |tags: &[&str]| tags.iter().map(|t| t.to_string()).collect();
+        assert_eq!(jaccard(&tags(&["a", "b"]), &tags(&["b", "c"])), 1.0 / 3.0);
+        assert_eq!(jaccard(&tags(&[]), &tags(&[])), 0.0);
+
+        let vector = |weights: &[(&'static str, f64)]| weights.iter().copied().collect();
+        let a = vector(&[("dog", 1.0), ("river", 1.0)]);
+        assert!((cosine(&a, &a) - 1.0).abs() < 1e-9);
+        assert_eq!(cosine(&a, &vector(&[("bread", 2.0)])), 0.0);
+        assert_eq!(cosine(&a, &vector(&[])), 0.0);
+    }
+}
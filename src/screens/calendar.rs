@@ -0,0 +1,206 @@
+use super::viewer::ViewerScreen;
+use super::{
+    entries_by_day, heat_color, render_day, render_instructions, render_title, screen_layout,
+    write_on,
+};
+use crate::app::{AppEvent, Context, Screen, Transition};
+use crate::diary_entry::DiaryEntry;
+use crate::keymap::{Command, Movement};
+use chrono::{Datelike, Days, Months, NaiveDate};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+/// What the calendar's shading shows.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Measure {
+    Words,
+    Entries,
+}
+
+impl Measure {
+    fn of(self, entries: &[&DiaryEntry]) -> usize {
+        match self {
+            Measure::Words => entries.iter().map(|e| e.word_count()).sum(),
+            Measure::Entries => entries.len(),
+        }
+    }
+}
+
+/// A month at a time, each day shaded by how much was written on it. Enter
+/// reads the selected day's entries, or starts one for it if there are none.
+pub struct CalendarScreen {
+    today: NaiveDate,
+    selected: NaiveDate,
+    measure: Measure,
+}
+
+impl CalendarScreen {
+    pub fn new(today: NaiveDate) -> Self {
+        CalendarScreen {
+            today,
+            selected: today,
+            measure: Measure::Words,
+        }
+    }
+
+    /// The same calendar, opened on `day` rather than today.
+    pub fn showing(self, day: NaiveDate) -> Self {
+        CalendarScreen {
+            selected: day,
+            ..self
+        }
+    }
+}
+
+impl Screen for CalendarScreen {
+    fn render(&mut self, f: &mut Frame, ctx: &Context) {
+        let days = entries_by_day(ctx.diary.get_entries());
+        let month = self.selected.with_day(1).unwrap();
+        let month_days: Vec<NaiveDate> = month
+            .iter_days()
+            .take_while(|d| d.month() == month.month())
+            .collect();
+        let value = |day: &NaiveDate| days.get(day).map_or(0, |e| self.measure.of(e));
+        let max = month_days.iter().map(value).max().unwrap_or(0);
+
+        let chunks = screen_layout(f.area(), Constraint::Min(10));
+        render_title(f, chunks[0], "Calendar");
+        let body = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(40)].as_ref())
+            .split(chunks[1]);
+
+        let mut legend = vec![Span::raw(" Less ")];
+        legend.extend(
+            (1..=4).map(|level| {
+                Span::styled("  ", Style::default().bg(heat_color(level, 4).unwrap()))
+            }),
+        );
+        legend.push(Span::raw(" More "));
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(month.format("%B %Y").to_string())
+            .title_bottom(Line::from(legend));
+        let grid = block.inner(body[0]);
+        f.render_widget(block, body[0]);
+
+        // Weeks start on Monday.
+        let offset = month.weekday().num_days_from_monday() as usize;
+        let weeks = (offset + month_days.len()).div_ceil(7);
+        let cell_width = grid.width / 7;
+        let cell_height = (grid.height.saturating_sub(1) / weeks as u16).clamp(1, 3);
+        for (column, name) in ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]
+            .iter()
+            .enumerate()
+        {
+            let header = Paragraph::new(format!(" {}", name))
+                .style(Style::default().add_modifier(Modifier::BOLD));
+            f.render_widget(header, cell(grid, column, 0, cell_width, 1));
+        }
+        for (i, day) in month_days.iter().enumerate() {
+            let (row, column) = ((offset + i) / 7, (offset + i) % 7);
+            let area = cell(
+                grid,
+                column,
+                1 + row as u16 * cell_height,
+                cell_width,
+                cell_height,
+            );
+            if area.is_empty() {
+                continue;
+            }
+
+            let value = value(day);
+            let mut style = match heat_color(value, max) {
+                Some(color) => Style::default().bg(color).fg(Color::White),
+                None => Style::default().fg(Color::DarkGray),
+            };
+            if *day == self.today {
+                style = style.add_modifier(Modifier::UNDERLINED);
+            }
+            if *day == self.selected {
+                style = style.add_modifier(Modifier::REVERSED | Modifier::BOLD);
+            }
+            let mut lines = vec![Line::raw(format!("{:>3}", day.day()))];
+            if value > 0 {
+                lines.push(Line::raw(match self.measure {
+                    Measure::Words => format!("{:>3}w", value),
+                    Measure::Entries => format!("{:>3}×", value),
+                }));
+            }
+            f.render_widget(Paragraph::new(lines).style(style), area);
+        }
+
+        let selected = days.get(&self.selected).cloned().unwrap_or_default();
+        render_day(f, body[1], self.selected, &selected, self.today);
+
+        render_instructions(
+            f,
+            chunks[2],
+            format!(
+                "Arrows: Move, PgUp/PgDn: Month, t: Today, Enter: Read or write, w: Write on this day, m: Shade by {}, Esc: Back",
+                match self.measure {
+                    Measure::Words => "entries",
+                    Measure::Entries => "words",
+                }
+            ),
+        );
+    }
+
+    fn handle_event(&mut self, event: AppEvent, ctx: &mut Context) -> Transition {
+        let AppEvent::Key(key) = event else {
+            return Transition::Stay;
+        };
+        let selected = self.selected;
+        let moved = match ctx.keymap.command(key) {
+            Some(Command::Move(Movement::Left)) => selected.checked_sub_days(Days::new(1)),
+            Some(Command::Move(Movement::Right)) => selected.checked_add_days(Days::new(1)),
+            Some(Command::Move(Movement::Up)) => selected.checked_sub_days(Days::new(7)),
+            Some(Command::Move(Movement::Down)) => selected.checked_add_days(Days::new(7)),
+            Some(Command::Move(Movement::PageUp)) => selected.checked_sub_months(Months::new(1)),
+            Some(Command::Move(Movement::PageDown)) => selected.checked_add_months(Months::new(1)),
+            Some(Command::Char('t')) => Some(self.today),
+            Some(Command::Char('m')) => {
+                self.measure = match self.measure {
+                    Measure::Words => Measure::Entries,
+                    Measure::Entries => Measure::Words,
+                };
+                None
+            }
+            Some(Command::Confirm) => {
+                let days = entries_by_day(ctx.diary.get_entries());
+                return match days.get(&selected) {
+                    Some(entries) => {
+                        let ids: Vec<usize> = entries.iter().map(|e| e.id).collect();
+                        let viewer = ViewerScreen::new(ctx, ids.clone(), ids[0]);
+                        Transition::Push(Box::new(viewer))
+                    }
+                    None => write_on(ctx, self.selected, self.today),
+                };
+            }
+            Some(Command::Char('w')) => return write_on(ctx, self.selected, self.today),
+            Some(Command::Cancel | Command::Char('q')) => return Transition::Pop(None),
+            _ => None,
+        };
+        if let Some(day) = moved {
+            self.selected = day;
+        }
+        Transition::Stay
+    }
+}
+
+/// The calendar cell in `column` at `row`, measured in lines from the top.
+fn cell(grid: Rect, column: usize, row: u16, width: u16, height: u16) -> Rect {
+    Rect {
+        x: grid.x + column as u16 * width,
+        y: grid.y + row,
+        width,
+        height,
+    }
+    .intersection(grid)
+}
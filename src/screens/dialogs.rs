@@ -0,0 +1,322 @@
+use super::editor::EditorScreen;
+use super::{input_block, popup_area, render_instructions, render_title, screen_layout};
+use crate::app::{AppEvent, Context, Outcome, Screen, Transition};
+use crate::diary_entry::DiaryEntry;
+use crate::drafts::Draft;
+use crate::keymap::{Command, Movement};
+use crate::text_area::TextArea;
+use ratatui::{
+    layout::{Alignment, Constraint},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+/// Asks a yes/no question in a popup. Only `y` answers yes.
+pub struct ConfirmScreen {
+    title: String,
+    question: String,
+}
+
+impl ConfirmScreen {
+    pub fn new(title: &str, question: &str) -> Self {
+        ConfirmScreen {
+            title: title.to_string(),
+            question: question.to_string(),
+        }
+    }
+}
+
+impl Screen for ConfirmScreen {
+    fn render(&mut self, f: &mut Frame, _ctx: &Context) {
+        render_question(f, &self.title, &self.question);
+    }
+
+    fn handle_event(&mut self, event: AppEvent, ctx: &mut Context) -> Transition {
+        match answer(event, ctx) {
+            Some(yes) => Transition::Pop(Some(Outcome::Confirmed(yes))),
+            None => Transition::Stay,
+        }
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
+
+/// Draws a yes/no question as a popup in the middle of the screen.
+fn render_question(f: &mut Frame, title: &str, question: &str) {
+    let mut text = Text::from(question.to_string());
+    text.push_line(Line::raw(""));
+    text.push_line(Line::styled(
+        "y: Yes, n: No",
+        Style::default().fg(Color::Yellow),
+    ));
+    let question = Paragraph::new(text)
+        .wrap(Wrap { trim: false })
+        .alignment(Alignment::Center);
+
+    let width = f.area().width.saturating_sub(4).min(72);
+    let height = question.line_count(width.saturating_sub(4)) as u16 + 2;
+    let area = popup_area(f, f.area(), width, height);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .title(title.to_string());
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    f.render_widget(
+        question,
+        inner.inner(ratatui::layout::Margin {
+            horizontal: 1,
+            vertical: 0,
+        }),
+    );
+}
+
+/// `Some(true)` for `y`, `Some(false)` for `n` or Esc.
+fn answer(event: AppEvent, ctx: &Context) -> Option<bool> {
+    let AppEvent::Key(key) = event else {
+        return None;
+    };
+    match ctx.keymap.command(key) {
+        Some(Command::Char('y' | 'Y')) => Some(true),
+        Some(Command::Char('n' | 'N') | Command::Cancel) => Some(false),
+        _ => None,
+    }
+}
+
+/// Asks which template to start a new entry from, in a popup, then opens
+/// the editor in its place. The first choice is a blank entry.
+#[derive(Default)]
+pub struct ChooseTemplateScreen {
+    selected: usize,
+}
+
+impl Screen for ChooseTemplateScreen {
+    fn render(&mut self, f: &mut Frame, ctx: &Context) {
+        let dim = Style::default().fg(Color::DarkGray);
+        let mut items = vec![ListItem::new("Blank entry")];
+        items.extend(ctx.templates.iter().map(|template| {
+            let mut line = Line::raw(template.name.clone());
+            if !template.tags.is_empty() {
+                line.push_span(Span::styled(format!("  {}", template.tags.join(", ")), dim));
+            }
+            ListItem::new(line)
+        }));
+
+        let height = items.len() as u16 + 2;
+        let area = popup_area(f, f.area(), 50, height);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title("New entry from")
+            .title_bottom(Line::styled("Enter: Choose, Esc: Cancel", dim));
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+            .highlight_symbol("> ");
+        f.render_stateful_widget(
+            list,
+            area,
+            &mut ListState::default().with_selected(Some(self.selected)),
+        );
+    }
+
+    fn handle_event(&mut self, event: AppEvent, ctx: &mut Context) -> Transition {
+        let AppEvent::Key(key) = event else {
+            return Transition::Stay;
+        };
+        match ctx.keymap.command(key) {
+            Some(Command::Move(Movement::Up)) => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            Some(Command::Move(Movement::Down)) => {
+                self.selected = (self.selected + 1).min(ctx.templates.len());
+            }
+            Some(Command::Confirm) => {
+                let editor = EditorScreen::new_entry(ctx, None);
+                let editor = match self.selected.checked_sub(1) {
+                    Some(i) => editor.with_template(&ctx.templates[i]),
+                    None => editor,
+                };
+                return Transition::Replace(Box::new(editor));
+            }
+            Some(Command::Cancel) => return Transition::Pop(None),
+            _ => {}
+        }
+        Transition::Stay
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
+
+/// Checks a prompt's input, explaining what is wrong with it.
+type Validator = Box<dyn Fn(&str) -> Result<(), String>>;
+
+/// Asks for a single line of text. `validate` is run on every change and its
+/// error is shown inline; Enter is ignored until the input is valid. Closes
+/// with the trimmed text, or nothing if cancelled.
+pub struct PromptScreen {
+    title: String,
+    label: String,
+    input: TextArea,
+    validate: Validator,
+}
+
+impl PromptScreen {
+    pub fn new(
+        title: &str,
+        label: &str,
+        initial: &str,
+        validate: impl Fn(&str) -> Result<(), String> + 'static,
+    ) -> Self {
+        PromptScreen {
+            title: title.to_string(),
+            label: label.to_string(),
+            input: TextArea::single_line(initial),
+            validate: Box::new(validate),
+        }
+    }
+
+    fn error(&self) -> Option<String> {
+        let text = self.input.text();
+        let trimmed = text.trim();
+        if trimmed.is_empty() {
+            None
+        } else {
+            (self.validate)(trimmed).err()
+        }
+    }
+}
+
+impl Screen for PromptScreen {
+    fn render(&mut self, f: &mut Frame, _ctx: &Context) {
+        let error = self.error();
+        let chunks = screen_layout(f.area(), Constraint::Length(3));
+        render_title(f, chunks[0], &self.title);
+        self.input.render(
+            f,
+            chunks[1],
+            input_block(&self.label, error.as_deref()),
+            true,
+        );
+        render_instructions(f, chunks[2], "Enter: Submit, Esc: Cancel");
+    }
+
+    fn handle_event(&mut self, event: AppEvent, ctx: &mut Context) -> Transition {
+        let AppEvent::Key(key) = event else {
+            return Transition::Stay;
+        };
+        match ctx.keymap.command(key) {
+            Some(Command::Confirm) if self.error().is_none() => {
+                Transition::Pop(Some(Outcome::Input(self.input.text().trim().to_string())))
+            }
+            Some(Command::Cancel) => Transition::Pop(None),
+            Some(command) => {
+                self.input.apply(command, &mut ctx.kill_ring);
+                Transition::Stay
+            }
+            None => Transition::Stay,
+        }
+    }
+}
+
+/// Offers, one at a time, to reopen the drafts still around from when the
+/// app last closed: they were being written when it did.
+pub struct RecoverDraftsScreen {
+    drafts: Vec<Draft>,
+}
+
+impl RecoverDraftsScreen {
+    /// `None` if there is nothing to recover. Drafts of entries deleted since
+    /// are dropped.
+    pub fn new(ctx: &mut Context) -> Option<Self> {
+        let mut drafts = ctx.drafts.get_drafts().clone();
+        drafts.retain(|draft| {
+            let exists = draft
+                .entry_id
+                .is_none_or(|id| draft_entry(ctx, id).is_some());
+            if !exists {
+                ctx.drafts.remove(draft.entry_id);
+            }
+            exists
+        });
+        // Offered from the end, so reverse to go in the order they were saved.
+        drafts.reverse();
+        (!drafts.is_empty()).then_some(RecoverDraftsScreen { drafts })
+    }
+
+    /// Moves on to the next draft, closing once there are none left.
+    fn next(&mut self) -> Transition {
+        self.drafts.pop();
+        if self.drafts.is_empty() {
+            Transition::Pop(None)
+        } else {
+            Transition::Stay
+        }
+    }
+}
+
+fn draft_entry(ctx: &Context, id: usize) -> Option<DiaryEntry> {
+    ctx.diary.get_entries().iter().find(|e| e.id == id).cloned()
+}
+
+impl Screen for RecoverDraftsScreen {
+    fn render(&mut self, f: &mut Frame, ctx: &Context) {
+        let Some(draft) = self.drafts.last() else {
+            return;
+        };
+        let what = match draft.entry_id.and_then(|id| draft_entry(ctx, id)) {
+            Some(entry) => format!(
+                "Your changes to \"{}\" weren't saved before the diary closed.",
+                entry.title()
+            ),
+            None => {
+                "A new entry you were writing wasn't saved before the diary closed.".to_string()
+            }
+        };
+        let preview = draft.content.lines().next().unwrap_or_default();
+        render_question(
+            f,
+            "Recover draft?",
+            &format!(
+                "{} The last autosave is from {}:\n\n{}\n\nRecover the draft?",
+                what,
+                draft.saved_at.format("%Y-%m-%d %H:%M"),
+                preview
+            ),
+        );
+    }
+
+    fn handle_event(&mut self, event: AppEvent, ctx: &mut Context) -> Transition {
+        let Some(draft) = self.drafts.last().cloned() else {
+            return Transition::Pop(None);
+        };
+        match answer(event, ctx) {
+            Some(true) => {
+                let editor = match draft.entry_id.and_then(|id| draft_entry(ctx, id)) {
+                    Some(entry) => EditorScreen::edit(ctx, &entry, Some(draft)),
+                    None => EditorScreen::new_entry(ctx, Some(draft)),
+                };
+                Transition::Push(Box::new(editor))
+            }
+            Some(false) => {
+                ctx.drafts.remove(draft.entry_id);
+                self.next()
+            }
+            None => Transition::Stay,
+        }
+    }
+
+    fn resume(&mut self, _outcome: Option<Outcome>, _ctx: &mut Context) -> Transition {
+        self.next()
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,669 @@
+use super::dialogs::ConfirmScreen;
+use super::{mood_stars, render_instructions, render_title, screen_layout};
+use crate::app::{AppEvent, Context, CursorShape, Outcome, Screen, Transition};
+use crate::config::EditingMode;
+use crate::diary_entry::DiaryEntry;
+use crate::drafts::Draft;
+use crate::external_editor::{self, Composed};
+use crate::keymap::{Command, Movement};
+use crate::markdown;
+use crate::templates::Template;
+use crate::text_area::{Snapshot, TextArea};
+use crate::undo::History;
+use crate::vim::{Vim, VimMode, VimOutcome};
+use chrono::{DateTime, Local, NaiveDate};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    symbols,
+    text::{Line, Span},
+    widgets::{Block, Borders, LineGauge, Paragraph, Wrap},
+    Frame,
+};
+use std::time::{Duration, Instant};
+
+/// How often the editor autosaves a draft while there are changes.
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(3);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EditorField {
+    Content,
+    Tags,
+}
+
+/// Both editor fields and the mood, as recorded for undo.
+struct EditorSnapshot {
+    content: Snapshot,
+    tags: Snapshot,
+    mood: Option<u8>,
+    focus: EditorField,
+}
+
+/// The kinds of change that coalesce into one undo step when repeated in the
+/// same field.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EditKind {
+    Typing,
+    Deleting,
+    Mood,
+    /// Everything typed in one visit to vim's insert mode.
+    Inserting,
+}
+
+/// The editor shared by the new and edit screens: the content and the tags
+/// are edited side by side, with Tab moving between them, until the entry
+/// is saved or discarded. Meanwhile the changes are autosaved as a draft.
+pub struct EditorScreen {
+    title: String,
+    entry: DiaryEntry,
+    /// New entries don't have an id until they are added to the diary.
+    entry_id: Option<usize>,
+    /// The earlier day a new entry is written for.
+    date: Option<NaiveDate>,
+    original: Draft,
+    last_draft: Draft,
+    last_autosave: Instant,
+    content: TextArea,
+    tags: TextArea,
+    mood: Option<u8>,
+    /// The writing prompt being answered, if any.
+    prompt: Option<String>,
+    /// The prompt of the day, offered on a new entry while it answers none.
+    /// Only Ctrl+T takes it up, so entries don't record prompts they ignore.
+    suggestion: Option<String>,
+    focus: EditorField,
+    history: History<EditorSnapshot, (EditorField, EditKind)>,
+    vim: Option<Vim>,
+    /// A notice such as an error from the external editor, shown until the
+    /// next key.
+    message: Option<Line<'static>>,
+}
+
+impl EditorScreen {
+    /// The editor for a new entry, starting from `draft` if one is being
+    /// recovered, and suggesting the prompt of the day. An empty entry is
+    /// not added when saved.
+    pub fn new_entry(ctx: &Context, draft: Option<Draft>) -> Self {
+        let mut entry = DiaryEntry::new(0, String::new(), Vec::new());
+        entry.timestamp = ctx.now();
+        let suggestion = ctx.prompts.of_the_day(ctx.today());
+        EditorScreen {
+            suggestion: suggestion.map(|p| p.text.clone()),
+            ..EditorScreen::open(ctx, "New Diary Entry", entry, draft)
+        }
+    }
+
+    /// The editor on `entry`, or on `draft` of it if one is being recovered.
+    pub fn edit(ctx: &Context, entry: &DiaryEntry, draft: Option<Draft>) -> Self {
+        EditorScreen::open(ctx, "Edit Diary Entry", entry.clone(), draft)
+    }
+
+    /// Dates the new entry to `date` rather than to when it is saved.
+    pub fn backdated_to(self, date: NaiveDate) -> Self {
+        EditorScreen {
+            title: format!("New Diary Entry for {}", date.format("%A, %-d %B %Y")),
+            date: Some(date),
+            ..self
+        }
+    }
+
+    /// Sets the new entry to answer `prompt` rather than the prompt of the
+    /// day.
+    pub fn answering(mut self, prompt: &str) -> Self {
+        self.prompt = Some(prompt.to_string());
+        self.suggestion = None;
+        self.original.prompt = self.prompt.clone();
+        self.last_draft.prompt = self.prompt.clone();
+        self
+    }
+
+    /// Starts the new entry from `template`, filled in for its day and
+    /// prompt, with the template's tags. A template with a place for the
+    /// prompt takes up the suggested one.
+    pub fn with_template(self, template: &Template) -> Self {
+        let editor = match (&self.prompt, &self.suggestion) {
+            (None, Some(suggestion)) if template.asks_prompt() => {
+                let suggestion = suggestion.clone();
+                self.answering(&suggestion)
+            }
+            _ => self,
+        };
+        editor.filled_from(template)
+    }
+
+    fn filled_from(self, template: &Template) -> Self {
+        let day = self.date.unwrap_or(self.entry.timestamp.date_naive());
+        let (text, cursor) = template.fill(day, self.prompt.as_deref());
+        let mut content = TextArea::markdown(&text);
+        if let Some(cursor) = cursor {
+            content.move_to(cursor, false);
+        }
+        let tags = template.tags.join(", ");
+        // Left as it is, the template doesn't count as a change.
+        let original = Draft {
+            content: text,
+            tags: tags.clone(),
+            ..self.original.clone()
+        };
+        let mut editor = EditorScreen {
+            content,
+            tags: TextArea::single_line(&tags),
+            last_draft: original.clone(),
+            original,
+            ..self
+        };
+        if let Some(vim) = &mut editor.vim {
+            vim.normalize(&mut editor.content);
+        }
+        editor
+    }
+
+    fn open(ctx: &Context, title: &str, entry: DiaryEntry, draft: Option<Draft>) -> Self {
+        let entry_id = (entry.id != 0).then_some(entry.id);
+        let original = Draft {
+            entry_id,
+            content: entry.content.clone(),
+            tags: entry.tags.join(", "),
+            mood: entry.mood,
+            saved_at: Local::now(),
+            date: None,
+            prompt: entry.prompt.clone(),
+        };
+        let date = draft.as_ref().and_then(|d| d.date);
+        let last_draft = draft.unwrap_or_else(|| original.clone());
+        let editor = EditorScreen {
+            title: title.to_string(),
+            entry,
+            entry_id,
+            date: None,
+            content: TextArea::markdown(&last_draft.content),
+            tags: TextArea::single_line(&last_draft.tags),
+            mood: last_draft.mood,
+            prompt: last_draft.prompt.clone(),
+            suggestion: None,
+            original,
+            last_draft,
+            last_autosave: Instant::now(),
+            focus: EditorField::Content,
+            history: History::default(),
+            vim: (ctx.config.editing_mode == EditingMode::Vim).then(Vim::new),
+            message: None,
+        };
+        match date {
+            Some(date) => editor.backdated_to(date),
+            None => editor,
+        }
+    }
+
+    fn current(&self) -> Draft {
+        Draft {
+            entry_id: self.entry_id,
+            content: self.content.text(),
+            tags: self.tags.text(),
+            mood: self.mood,
+            saved_at: Local::now(),
+            date: self.date,
+            prompt: self.prompt.clone(),
+        }
+    }
+
+    fn autosave(&mut self, ctx: &mut Context) {
+        if self.last_autosave.elapsed() < AUTOSAVE_INTERVAL {
+            return;
+        }
+        let current = self.current();
+        if !current.same_text(&self.last_draft) {
+            ctx.drafts.put(current.clone());
+            self.last_draft = current;
+        }
+        self.last_autosave = Instant::now();
+    }
+
+    fn snapshot(&self) -> EditorSnapshot {
+        EditorSnapshot {
+            content: self.content.snapshot(),
+            tags: self.tags.snapshot(),
+            mood: self.mood,
+            focus: self.focus,
+        }
+    }
+
+    /// When a new entry is written: now, or at this time of day on the day it
+    /// is backdated to.
+    fn timestamp(&self, ctx: &Context) -> DateTime<Local> {
+        let now = ctx.now();
+        self.date
+            .and_then(|date| {
+                date.and_time(now.time())
+                    .and_local_timezone(Local)
+                    .earliest()
+            })
+            .unwrap_or(now)
+    }
+
+    /// Closes the editor, adding or updating the entry if `save`.
+    fn finish(&mut self, ctx: &mut Context, save: bool) -> Transition {
+        ctx.drafts.remove(self.entry_id);
+        if save {
+            let entry = DiaryEntry {
+                content: self.content.text(),
+                tags: parse_tags(&self.tags.text()),
+                mood: self.mood,
+                prompt: self.prompt.clone(),
+                ..self.entry.clone()
+            };
+            match self.entry_id {
+                Some(_) => ctx.diary.update_entry(entry, ctx.now()),
+                None if entry.content.trim().is_empty() => return Transition::Pop(None),
+                None => ctx.diary.add_entry(DiaryEntry {
+                    timestamp: self.timestamp(ctx),
+                    ..entry
+                }),
+            }
+            ctx.notify("Entry saved");
+        }
+        Transition::Pop(None)
+    }
+}
+
+impl Screen for EditorScreen {
+    fn render(&mut self, f: &mut Frame, ctx: &Context) {
+        let chunks = screen_layout(f.area(), Constraint::Min(13));
+        render_title(f, chunks[0], &self.title);
+
+        let words = self.content.text().split_whitespace().count();
+        let goal = ctx.config.goals.words_per_day;
+        let fields = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Length(if self.prompt.is_some() || self.suggestion.is_some() {
+                        1
+                    } else {
+                        0
+                    }),
+                    Constraint::Min(10),
+                    Constraint::Length(if goal.is_some() { 1 } else { 0 }),
+                    Constraint::Length(3),
+                ]
+                .as_ref(),
+            )
+            .split(chunks[1]);
+        if let Some(goal) = goal {
+            // Everything else written that day counts towards the goal too.
+            let day = self.date.unwrap_or(self.entry.timestamp.date_naive());
+            let others: usize = ctx
+                .diary
+                .get_entries()
+                .iter()
+                .filter(|e| e.timestamp.date_naive() == day && Some(e.id) != self.entry_id)
+                .map(|e| e.word_count())
+                .sum();
+            render_goal(f, fields[2], others + words, goal);
+        }
+        if let Some(prompt) = &self.prompt {
+            let prompt = Line::from(vec![
+                Span::styled("Prompt: ", Style::default().fg(Color::Yellow)),
+                Span::styled(
+                    prompt.clone(),
+                    Style::default().add_modifier(Modifier::ITALIC),
+                ),
+            ]);
+            f.render_widget(Paragraph::new(prompt), fields[0]);
+        } else if let Some(suggestion) = &self.suggestion {
+            let suggestion = Line::styled(
+                format!("Prompt of the day: {} (Ctrl+T to answer it)", suggestion),
+                Style::default().fg(Color::DarkGray),
+            );
+            f.render_widget(Paragraph::new(suggestion), fields[0]);
+        }
+        let content_area = if ctx.preview {
+            let halves = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(fields[1]);
+            render_preview(f, halves[1], &self.content);
+            halves[0]
+        } else {
+            fields[1]
+        };
+        let focus = self.focus;
+        self.content.render(
+            f,
+            content_area,
+            editor_block(
+                format!(
+                    "Content ({} word{})",
+                    words,
+                    if words == 1 { "" } else { "s" }
+                ),
+                focus == EditorField::Content,
+            ),
+            focus == EditorField::Content,
+        );
+        self.tags.render(
+            f,
+            fields[3],
+            editor_block(tags_title(self.mood), focus == EditorField::Tags),
+            focus == EditorField::Tags,
+        );
+
+        let instructions = match (&self.message, &self.vim, ctx.keymap.profile()) {
+            (Some(message), _, _) => message.clone(),
+            (None, Some(vim), _) => vim_status_line(vim, focus),
+            (None, None, EditingMode::Emacs) => Line::raw(
+                "Tab: Switch field, C-Space: Mark, C-k/C-w: Kill, M-w: Copy, C-y/M-y: Yank, C-/: Undo, C-o: $EDITOR, M-p: Preview, C-t: Prompt, C-s: Save, C-g: Cancel",
+            ),
+            (None, None, _) => Line::raw(
+                "Tab: Next field, Shift+Move: Select, Ctrl+Z/Y: Undo/Redo, Ctrl+O: $EDITOR, Ctrl+P: Preview, Ctrl+T: Prompt, Ctrl+S: Save, Esc: Cancel",
+            ),
+        };
+        render_instructions(f, chunks[2], instructions);
+    }
+
+    fn handle_event(&mut self, event: AppEvent, ctx: &mut Context) -> Transition {
+        // Checked on keys too, so that steady typing doesn't hold it off.
+        self.autosave(ctx);
+        let AppEvent::Key(key) = event else {
+            return Transition::Stay;
+        };
+
+        self.message = None;
+        let current = self.current();
+        let changed = !current.same_text(&self.original);
+        let command = ctx.keymap.command(key);
+        let before = self.snapshot();
+        let focus = self.focus;
+        let area = match focus {
+            EditorField::Content => &mut self.content,
+            EditorField::Tags => &mut self.tags,
+        };
+        let restored = match (focus, command, self.vim.as_mut()) {
+            (_, Some(Command::ExternalEditor), _) => return Transition::Suspend,
+            (_, Some(Command::NextPrompt), _) => {
+                self.prompt = ctx
+                    .prompts
+                    .after(self.prompt.as_deref(), self.suggestion.as_deref())
+                    .map(|p| p.text.clone());
+                None
+            }
+            (_, Some(Command::TogglePreview), _) => {
+                ctx.preview = !ctx.preview;
+                None
+            }
+            (_, Some(Command::NextField), _) => {
+                self.focus = match focus {
+                    EditorField::Content => EditorField::Tags,
+                    EditorField::Tags => EditorField::Content,
+                };
+                self.history.break_run();
+                None
+            }
+            (EditorField::Content, _, Some(vim)) => match vim.input(area, key) {
+                VimOutcome::Save => return self.finish(ctx, true),
+                VimOutcome::Discard => return self.finish(ctx, false),
+                VimOutcome::Quit if !changed => return self.finish(ctx, false),
+                VimOutcome::Quit => {
+                    self.message = Some(Line::styled(
+                        "No write since last change (add ! to override)",
+                        Style::default().fg(Color::Red),
+                    ));
+                    None
+                }
+                VimOutcome::SaveDraft => {
+                    ctx.drafts.put(current.clone());
+                    self.last_draft = current;
+                    self.message = Some(Line::raw("Draft saved"));
+                    None
+                }
+                VimOutcome::Undo => self.history.undo(before),
+                VimOutcome::Redo => self.history.redo(before),
+                // Everything typed until insert mode is left is one
+                // undo step, together with the command that entered
+                // it; any other command is a step of its own.
+                VimOutcome::Insert => {
+                    if let Some(command) = command {
+                        if area.apply(command, &mut ctx.kill_ring) {
+                            self.history
+                                .record(before, Some((focus, EditKind::Inserting)));
+                        }
+                    }
+                    None
+                }
+                VimOutcome::Changed if vim.mode() == VimMode::Insert => {
+                    self.history
+                        .record(before, Some((focus, EditKind::Inserting)));
+                    None
+                }
+                VimOutcome::Changed => {
+                    self.history.record(before, None);
+                    None
+                }
+                VimOutcome::Unchanged => {
+                    if vim.mode() != VimMode::Insert {
+                        self.history.break_run();
+                    }
+                    None
+                }
+            },
+            (EditorField::Tags, Some(Command::Cancel), Some(_)) => {
+                self.focus = EditorField::Content;
+                self.history.break_run();
+                None
+            }
+            (_, Some(Command::Cancel), _) if area.selection().is_some() => {
+                area.clear_selection();
+                None
+            }
+            (_, Some(Command::Cancel), _) if changed => {
+                let confirm =
+                    ConfirmScreen::new("Discard changes?", "Discard the changes to this entry?");
+                return Transition::Push(Box::new(confirm));
+            }
+            (_, Some(Command::Cancel), _) => return self.finish(ctx, false),
+            (_, Some(Command::Save), _) => return self.finish(ctx, true),
+            // In vim mode the tags take the standard keys, undo included.
+            (EditorField::Tags, Some(Command::Undo), _) | (_, Some(Command::Undo), None) => {
+                self.history.undo(before)
+            }
+            (EditorField::Tags, Some(Command::Redo), _) | (_, Some(Command::Redo), None) => {
+                self.history.redo(before)
+            }
+            (
+                EditorField::Tags,
+                Some(Command::Move(movement @ (Movement::Up | Movement::Down))),
+                _,
+            ) => {
+                self.mood = match movement {
+                    Movement::Up => Some(self.mood.map_or(3, |m| m.saturating_add(1).min(5))),
+                    _ => self.mood.and_then(|m| (m > 1).then(|| m - 1)),
+                };
+                if self.mood != before.mood {
+                    self.history.record(before, Some((focus, EditKind::Mood)));
+                }
+                None
+            }
+            (field, Some(command), _) => {
+                let run = edit_kind(command, area.selection().is_some()).map(|k| (field, k));
+                if area.apply(command, &mut ctx.kill_ring) {
+                    self.history.record(before, run);
+                    // A run of typing ends with the word it completes.
+                    if ends_word(command) {
+                        self.history.break_run();
+                    }
+                } else {
+                    self.history.break_run();
+                }
+                None
+            }
+            (_, None, _) => None,
+        };
+
+        if let Some(snapshot) = restored {
+            self.content.restore(snapshot.content);
+            self.tags.restore(snapshot.tags);
+            self.mood = snapshot.mood;
+            self.focus = snapshot.focus;
+            if let Some(vim) = &mut self.vim {
+                vim.normalize(&mut self.content);
+            }
+        }
+        Transition::Stay
+    }
+
+    /// Only the question whether to discard the changes returns here.
+    fn resume(&mut self, outcome: Option<Outcome>, ctx: &mut Context) -> Transition {
+        match outcome {
+            Some(Outcome::Confirmed(true)) => self.finish(ctx, false),
+            _ => Transition::Stay,
+        }
+    }
+
+    /// A block cursor in vim's normal and visual mode, a bar when typing.
+    fn cursor_shape(&self) -> CursorShape {
+        match &self.vim {
+            None => CursorShape::Default,
+            Some(vim) if self.focus == EditorField::Tags || vim.mode() == VimMode::Insert => {
+                CursorShape::Bar
+            }
+            Some(_) => CursorShape::Block,
+        }
+    }
+
+    /// Hands the entry to `$EDITOR`.
+    fn run_suspended(&mut self, _ctx: &mut Context) {
+        let before = self.snapshot();
+        let composed = Composed {
+            content: self.content.text(),
+            tags: parse_tags(&self.tags.text()),
+            mood: self.mood,
+        };
+        match external_editor::edit(&composed) {
+            Ok(composed) => {
+                self.content = TextArea::markdown(&composed.content);
+                self.tags = TextArea::single_line(&composed.tags.join(", "));
+                self.mood = composed.mood;
+                self.history.record(before, None);
+                if let Some(vim) = &mut self.vim {
+                    vim.normalize(&mut self.content);
+                }
+            }
+            Err(error) => {
+                self.message = Some(Line::styled(error, Style::default().fg(Color::Red)));
+            }
+        }
+    }
+}
+
+/// How a command that changes the text is grouped for undo. Replacing a
+/// selection is always a step of its own.
+fn edit_kind(command: Command, has_selection: bool) -> Option<EditKind> {
+    if has_selection {
+        return None;
+    }
+    match command {
+        Command::Char(_) | Command::Confirm => Some(EditKind::Typing),
+        Command::DeleteBackward | Command::DeleteForward => Some(EditKind::Deleting),
+        _ => None,
+    }
+}
+
+fn ends_word(command: Command) -> bool {
+    match command {
+        Command::Char(c) => c.is_whitespace(),
+        Command::Confirm => true,
+        _ => false,
+    }
+}
+
+/// The editor's status line in vim mode: the mode, any command being typed
+/// and the keys to finish.
+fn vim_status_line(vim: &Vim, focus: EditorField) -> Line<'static> {
+    let (label, color) = match (focus, vim.mode()) {
+        (EditorField::Tags, _) => ("TAGS", Color::Blue),
+        (_, VimMode::Normal) => (VimMode::Normal.label(), Color::Green),
+        (_, VimMode::Insert) => (VimMode::Insert.label(), Color::Blue),
+        (_, mode) => (mode.label(), Color::Magenta),
+    };
+    let hints = match focus {
+        EditorField::Content => {
+            "ZZ or :wq: Save, :q!: Discard, u/Ctrl+R: Undo/Redo, Ctrl+P: Preview, Tab: Switch field"
+        }
+        EditorField::Tags => {
+            "Esc: Back to content, Up/Down: Mood, Ctrl+Z/Y: Undo/Redo, Tab: Switch field"
+        }
+    };
+    Line::from(vec![
+        Span::styled(
+            format!(" {} ", label),
+            Style::default()
+                .fg(Color::Black)
+                .bg(color)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(format!(" {:<12} ", vim.status())),
+        Span::raw(hints),
+    ])
+}
+
+/// The entry as the viewer will show it, scrolled along with the cursor.
+fn render_preview(f: &mut Frame, area: Rect, content: &TextArea) {
+    let rendered = markdown::render(&content.text());
+    let height = area.height.saturating_sub(2) as usize;
+    // Source and rendered lines don't match up, so scroll in proportion.
+    let progress = content.cursor().row as f64 / content.lines().len().max(1) as f64;
+    let scroll = (progress * rendered.len() as f64) as usize;
+    let scroll = scroll
+        .saturating_sub(height / 2)
+        .min(rendered.len().saturating_sub(height));
+    let preview = Paragraph::new(rendered)
+        .block(Block::default().borders(Borders::ALL).title("Preview"))
+        .wrap(Wrap { trim: false })
+        .scroll((scroll as u16, 0));
+    f.render_widget(preview, area);
+}
+
+/// Progress towards the day's word goal, as a bar across the editor.
+fn render_goal(f: &mut Frame, area: Rect, words: usize, goal: usize) {
+    let (label, color) = if words >= goal {
+        (
+            format!("Daily goal met: {}/{} words ", words, goal),
+            Color::Green,
+        )
+    } else {
+        (
+            format!("Daily goal: {}/{} words ", words, goal),
+            Color::Cyan,
+        )
+    };
+    let gauge = LineGauge::default()
+        .ratio((words as f64 / goal.max(1) as f64).min(1.0))
+        .label(label)
+        .filled_style(Style::default().fg(color))
+        .line_set(symbols::line::THICK);
+    f.render_widget(gauge, area);
+}
+
+/// Block around an editor field; the focused one is highlighted.
+fn editor_block(title: String, focused: bool) -> Block<'static> {
+    let block = Block::default().borders(Borders::ALL).title(title);
+    if focused {
+        block.border_style(Style::default().fg(Color::Cyan))
+    } else {
+        block
+    }
+}
+
+/// Splits the comma-separated tags input, dropping empty tags.
+fn parse_tags(tags: &str) -> Vec<String> {
+    tags.split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Title of the tags input, which also shows the mood that Up/Down adjust.
+fn tags_title(mood: Option<u8>) -> String {
+    let mood = mood.map_or("not set".to_string(), mood_stars);
+    format!("Tags (comma-separated) | Mood: {} (Up/Down)", mood)
+}
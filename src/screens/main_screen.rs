@@ -0,0 +1,616 @@
+use super::calendar::CalendarScreen;
+use super::dialogs::{ChooseTemplateScreen, ConfirmScreen, PromptScreen};
+use super::editor::EditorScreen;
+use super::prompts::PromptsScreen;
+use super::search::{SearchQueryScreen, SearchResultsScreen};
+use super::stats::StatsScreen;
+use super::tasks::OpenTasksScreen;
+use super::viewer::ViewerScreen;
+use super::year::YearScreen;
+use super::{
+    cycle_list_order, entry_ids, entry_list_item, entry_row, list_items, mood_stars,
+    render_instructions, render_title, row_of, screen_layout, selected_entry_id, step_entry_row,
+};
+use crate::app::{AppEvent, Context, Outcome, Screen, Transition};
+use crate::date_expr;
+use crate::diary_entry::DiaryEntry;
+use crate::keymap::{Command, Movement};
+use crate::list_order::{ListRow, ListScreen};
+use crate::markdown;
+use crate::on_this_day::{self, Memory};
+use crate::stats;
+use chrono::NaiveDate;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+/// The panels that can have the keys, in the order Tab goes through them.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum Panel {
+    #[default]
+    Entries,
+    SavedSearches,
+    OnThisDay,
+}
+
+/// What the screen shows that takes going through every entry, kept until the
+/// diary or the day changes rather than worked out on every frame.
+#[derive(Default)]
+struct Cached {
+    key: Option<(u64, NaiveDate)>,
+    /// The number of entries each saved search finds, or `!` if it no
+    /// longer compiles.
+    search_counts: Vec<String>,
+    habit_progress: Option<Line<'static>>,
+    /// The ids of the entries to look back on, with how long ago they were.
+    memories: Vec<(usize, String)>,
+    /// The last entry previewed, by id, as rendered.
+    preview: Option<(usize, Vec<Line<'static>>)>,
+}
+
+/// The screen the app opens on: the entries on the left, with the selected
+/// one previewed on the right, and a key for everything else.
+#[derive(Default)]
+pub struct MainScreen {
+    selected_index: usize,
+    /// An entry to select once the rows are next arranged.
+    reselect: Option<usize>,
+    /// The entry waiting for the answer to whether to delete it.
+    deleting: Option<usize>,
+    /// The saved search waiting for the answer to whether to delete it.
+    deleting_search: Option<usize>,
+    /// Whether On this day has been put away.
+    hide_on_this_day: bool,
+    /// The panel the keys go to.
+    focus: Panel,
+    search_index: usize,
+    memory_index: usize,
+    cached: Cached,
+}
+
+impl MainScreen {
+    fn selected(&mut self, rows: &[ListRow]) -> Option<usize> {
+        if let Some(id) = self.reselect.take() {
+            self.selected_index = row_of(rows, id);
+        }
+        entry_row(rows, self.selected_index)
+    }
+
+    /// Works out again what is cached if the diary or the day has changed.
+    fn refresh(&mut self, ctx: &Context) {
+        let key = (ctx.diary.revision(), ctx.today());
+        if self.cached.key == Some(key) {
+            return;
+        }
+        let diary = &ctx.diary;
+        let search_counts = diary
+            .get_saved_searches()
+            .iter()
+            .map(|saved| match saved.query.compile() {
+                Ok(search) => diary.search(&search).len().to_string(),
+                Err(_) => "!".to_string(),
+            })
+            .collect();
+        let memories =
+            on_this_day::memories(diary.get_entries(), key.1, ctx.config.on_this_day_recent);
+        self.cached = Cached {
+            key: Some(key),
+            search_counts,
+            habit_progress: habit_progress(ctx),
+            memories: memories
+                .into_iter()
+                .map(|memory| (memory.entry.id, memory.when))
+                .collect(),
+            preview: None,
+        };
+    }
+
+    /// The entries to look back on today, and whether they are shown.
+    fn memories<'a>(&mut self, ctx: &'a Context) -> (Vec<Memory<'a>>, bool) {
+        self.refresh(ctx);
+        let entries = ctx.diary.get_entries();
+        let memories: Vec<Memory> = self
+            .cached
+            .memories
+            .iter()
+            .filter_map(|(id, when)| {
+                let entry = entries.iter().find(|e| e.id == *id)?;
+                Some(Memory {
+                    when: when.clone(),
+                    entry,
+                })
+            })
+            .collect();
+        let shown = !self.hide_on_this_day && !memories.is_empty();
+        (memories, shown)
+    }
+
+    /// The panel the keys go to, falling back to the entries when the focused
+    /// panel has gone.
+    fn focus(&self, ctx: &Context, on_this_day_shown: bool) -> Panel {
+        match self.focus {
+            Panel::SavedSearches if ctx.diary.get_saved_searches().is_empty() => Panel::Entries,
+            Panel::OnThisDay if !on_this_day_shown => Panel::Entries,
+            focus => focus,
+        }
+    }
+}
+
+impl Screen for MainScreen {
+    fn render(&mut self, f: &mut Frame, ctx: &Context) {
+        let diary_state = &ctx.diary;
+        let order = diary_state.get_list_order(ListScreen::Main);
+        let rows = order.arrange(ctx.visible_entries());
+        let selected = self.selected(&rows);
+        let entries_title = match ctx.date_filter_range() {
+            Some((expression, range)) => order.title(&format!(
+                "Entries: {} ({} to {})",
+                expression,
+                range.start.format("%Y-%m-%d"),
+                range.end.format("%Y-%m-%d")
+            )),
+            None => order.title("Entries"),
+        };
+
+        self.refresh(ctx);
+        let chunks = screen_layout(f.area(), Constraint::Min(0));
+        render_title(f, chunks[0], "Personal Diary");
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(45), Constraint::Percentage(55)].as_ref())
+            .split(chunks[1]);
+
+        let saved_searches = diary_state.get_saved_searches();
+        let left = if saved_searches.is_empty() {
+            [columns[0], Rect::default()]
+        } else {
+            let height = (saved_searches.len() as u16 + 2).min(columns[0].height / 3);
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(height)].as_ref())
+                .split(columns[0]);
+            [rows[0], rows[1]]
+        };
+
+        let mut entries_block = Block::default().borders(Borders::ALL).title(entries_title);
+        if let Some(progress) = self.cached.habit_progress.clone() {
+            entries_block = entries_block.title_bottom(progress.right_aligned());
+        }
+        let entries_list = List::new(list_items(&rows, entry_list_item))
+            .block(entries_block)
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+            .highlight_symbol("> ");
+        f.render_stateful_widget(
+            entries_list,
+            left[0],
+            &mut ListState::default().with_selected(selected),
+        );
+
+        let (memories, on_this_day_shown) = self.memories(ctx);
+        let focus = self.focus(ctx, on_this_day_shown);
+        if !saved_searches.is_empty() {
+            let folders: Vec<ListItem> = saved_searches
+                .iter()
+                .enumerate()
+                .zip(&self.cached.search_counts)
+                .map(|((i, saved), count)| {
+                    let shortcut = if i < 9 {
+                        format!("{} ", i + 1)
+                    } else {
+                        "  ".to_string()
+                    };
+                    ListItem::new(Line::from(vec![
+                        Span::styled(shortcut, Style::default().add_modifier(Modifier::BOLD)),
+                        Span::raw(saved.name.clone()),
+                        Span::styled(
+                            format!(" ({})", count),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                    ]))
+                })
+                .collect();
+
+            self.search_index = self.search_index.min(saved_searches.len() - 1);
+            let focused = focus == Panel::SavedSearches;
+            let mut block = Block::default()
+                .borders(Borders::ALL)
+                .title("Saved Searches");
+            if focused {
+                block = block
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .title_bottom(Line::styled(
+                        "Enter: Open, d: Delete",
+                        Style::default().fg(Color::DarkGray),
+                    ));
+            }
+            let folders_list = List::new(folders)
+                .block(block)
+                .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+                .highlight_symbol("> ");
+            f.render_stateful_widget(
+                folders_list,
+                left[1],
+                &mut ListState::default().with_selected(focused.then_some(self.search_index)),
+            );
+        }
+
+        let right = if on_this_day_shown {
+            let height = (memories.len() as u16 + 2).clamp(3, columns[1].height / 3);
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(height)].as_ref())
+                .split(columns[1]);
+            [rows[0], rows[1]]
+        } else {
+            [columns[1], Rect::default()]
+        };
+        let preview = selected_entry(&rows, selected).map(|entry| {
+            let content = match &self.cached.preview {
+                Some((id, content)) if *id == entry.id => content.clone(),
+                _ => {
+                    let content = markdown::render(&entry.content);
+                    self.cached.preview = Some((entry.id, content.clone()));
+                    content
+                }
+            };
+            (entry, content)
+        });
+        render_entry_preview(f, right[0], preview);
+        if on_this_day_shown {
+            self.memory_index = self.memory_index.min(memories.len() - 1);
+            let focused = focus == Panel::OnThisDay;
+            render_on_this_day(f, right[1], &memories, focused.then_some(self.memory_index));
+        }
+
+        let key = |k: &'static str| Span::styled(k, Style::default().add_modifier(Modifier::BOLD));
+        let controls = if diary_state.get_entries().is_empty() {
+            Line::from(vec![
+                Span::raw("Press "),
+                key("w"),
+                Span::raw(" to write, "),
+                key("q"),
+                Span::raw(" to quit"),
+            ])
+        } else {
+            // Kept to two lines at 80 columns, leaving the third for the
+            // hints added below.
+            Line::from(vec![
+                key("Enter"),
+                Span::raw(" to read, "),
+                key("w"),
+                Span::raw("/"),
+                key("e"),
+                Span::raw("/"),
+                key("d"),
+                Span::raw(" to write/edit/delete, "),
+                key("s"),
+                Span::raw("/"),
+                key("f"),
+                Span::raw(" to search/filter by date, "),
+                key("t"),
+                Span::raw("/"),
+                key("i"),
+                Span::raw("/"),
+                key("p"),
+                Span::raw(" for tasks/stats/prompts, "),
+                key("c"),
+                Span::raw("/"),
+                key("y"),
+                Span::raw(" for the month/year, "),
+                key("o"),
+                Span::raw("/"),
+                key("g"),
+                Span::raw(" to sort/group, "),
+                key("q"),
+                Span::raw(" to quit"),
+            ])
+        };
+        // The hints that only apply now go on a line of their own, so they
+        // are never cut off at the end of the others.
+        let mut extra = Line::default();
+        if !memories.is_empty() {
+            extra
+                .spans
+                .extend([key("h"), Span::raw(" to show/hide On this day")]);
+        }
+        if !saved_searches.is_empty() {
+            if !extra.spans.is_empty() {
+                extra.spans.push(Span::raw(", "));
+            }
+            extra.spans.extend([
+                key("Tab"),
+                Span::raw(" or "),
+                key("1-9"),
+                Span::raw(" for saved searches"),
+            ]);
+        }
+        render_instructions(f, chunks[2], Text::from(vec![controls, extra]));
+    }
+
+    fn handle_event(&mut self, event: AppEvent, ctx: &mut Context) -> Transition {
+        let AppEvent::Key(key) = event else {
+            return Transition::Stay;
+        };
+        let has_entries = !ctx.diary.get_entries().is_empty();
+        let order = ctx.diary.get_list_order(ListScreen::Main);
+        let rows = order.arrange(ctx.visible_entries());
+        let selected = self.selected(&rows);
+        let entry = selected_entry(&rows, selected);
+        let (memories, on_this_day_shown) = self.memories(ctx);
+        let saved_searches = ctx.diary.get_saved_searches().len();
+        let focus = self.focus(ctx, on_this_day_shown);
+        match ctx.keymap.command(key) {
+            Some(Command::NextField) if saved_searches > 0 || on_this_day_shown => {
+                self.focus = match focus {
+                    Panel::Entries if saved_searches > 0 => Panel::SavedSearches,
+                    Panel::Entries | Panel::SavedSearches if on_this_day_shown => Panel::OnThisDay,
+                    _ => Panel::Entries,
+                };
+                Transition::Stay
+            }
+            Some(Command::Char('h')) if !memories.is_empty() => {
+                self.hide_on_this_day = on_this_day_shown;
+                if focus == Panel::OnThisDay {
+                    self.focus = Panel::Entries;
+                }
+                Transition::Stay
+            }
+            Some(Command::Cancel) if focus == Panel::SavedSearches => {
+                self.focus = Panel::Entries;
+                Transition::Stay
+            }
+            Some(Command::Move(Movement::Up)) if focus == Panel::SavedSearches => {
+                self.search_index = self.search_index.saturating_sub(1);
+                Transition::Stay
+            }
+            Some(Command::Move(Movement::Down)) if focus == Panel::SavedSearches => {
+                self.search_index = (self.search_index + 1).min(saved_searches - 1);
+                Transition::Stay
+            }
+            Some(Command::Confirm) if focus == Panel::SavedSearches => {
+                let index = self.search_index.min(saved_searches - 1);
+                Transition::Push(Box::new(SearchResultsScreen::saved(ctx, index)))
+            }
+            Some(Command::Char('d') | Command::DeleteForward) if focus == Panel::SavedSearches => {
+                let index = self.search_index.min(saved_searches - 1);
+                self.deleting_search = Some(index);
+                let question = format!(
+                    "Delete the saved search \"{}\"?",
+                    ctx.diary.get_saved_searches()[index].name
+                );
+                Transition::Push(Box::new(ConfirmScreen::new(
+                    "Delete saved search?",
+                    &question,
+                )))
+            }
+            Some(Command::Cancel) if focus == Panel::OnThisDay => {
+                self.hide_on_this_day = true;
+                self.focus = Panel::Entries;
+                Transition::Stay
+            }
+            Some(Command::Move(Movement::Up)) if focus == Panel::OnThisDay => {
+                self.memory_index = self.memory_index.saturating_sub(1);
+                Transition::Stay
+            }
+            Some(Command::Move(Movement::Down)) if focus == Panel::OnThisDay => {
+                self.memory_index = (self.memory_index + 1).min(memories.len() - 1);
+                Transition::Stay
+            }
+            Some(Command::Confirm | Command::Char('v')) if focus == Panel::OnThisDay => {
+                let ids: Vec<usize> = memories.iter().map(|m| m.entry.id).collect();
+                let id = ids[self.memory_index.min(ids.len() - 1)];
+                Transition::Push(Box::new(ViewerScreen::new(ctx, ids, id)))
+            }
+            Some(Command::Move(Movement::Up)) => {
+                self.selected_index = step_entry_row(&rows, selected, false);
+                Transition::Stay
+            }
+            Some(Command::Move(Movement::Down)) => {
+                self.selected_index = step_entry_row(&rows, selected, true);
+                Transition::Stay
+            }
+            Some(Command::Confirm | Command::Char('v')) => match entry {
+                Some(entry) => {
+                    let viewer = ViewerScreen::new(ctx, entry_ids(&rows), entry.id);
+                    Transition::Push(Box::new(viewer))
+                }
+                None => Transition::Stay,
+            },
+            Some(Command::Char('e')) => match entry {
+                Some(entry) => {
+                    // Its place in the list may change with the edit.
+                    self.reselect = Some(entry.id);
+                    Transition::Push(Box::new(EditorScreen::edit(ctx, entry, None)))
+                }
+                None => Transition::Stay,
+            },
+            Some(Command::Char('d')) => match entry {
+                Some(entry) => {
+                    self.deleting = Some(entry.id);
+                    let question = format!("Delete \"{}\"? This can't be undone.", entry.title());
+                    Transition::Push(Box::new(ConfirmScreen::new("Delete entry?", &question)))
+                }
+                None => Transition::Stay,
+            },
+            Some(Command::Char('w')) if ctx.templates.is_empty() => {
+                Transition::Push(Box::new(EditorScreen::new_entry(ctx, None)))
+            }
+            Some(Command::Char('w')) => Transition::Push(Box::new(ChooseTemplateScreen::default())),
+            Some(Command::Char('q')) => Transition::Quit,
+            Some(Command::Char('s')) if has_entries => {
+                Transition::Push(Box::new(SearchQueryScreen::default()))
+            }
+            Some(Command::Char('f')) if has_entries => {
+                let today = ctx.today();
+                Transition::Push(Box::new(PromptScreen::new(
+                    "Filter Entries by Date",
+                    "Date (e.g. 2023-03, last week, 3 days ago, 2023-01..2023-03)",
+                    &ctx.date_filter.clone().unwrap_or_default(),
+                    move |input| date_expr::parse(input, today).map(|_| ()),
+                )))
+            }
+            Some(Command::Char('t')) if has_entries => {
+                Transition::Push(Box::new(OpenTasksScreen::default()))
+            }
+            Some(Command::Char('p')) if has_entries => {
+                Transition::Push(Box::new(PromptsScreen::default()))
+            }
+            Some(Command::Char('i')) if has_entries => Transition::Push(Box::new(StatsScreen)),
+            Some(Command::Char('c')) => {
+                let today = ctx.today();
+                Transition::Push(Box::new(CalendarScreen::new(today)))
+            }
+            Some(Command::Char('y')) => {
+                let today = ctx.today();
+                Transition::Push(Box::new(YearScreen::new(today)))
+            }
+            Some(Command::Char(c @ ('o' | 'g'))) => {
+                self.reselect = selected_entry_id(&rows, selected);
+                cycle_list_order(ctx, ListScreen::Main, c);
+                Transition::Stay
+            }
+            Some(Command::Char(c @ '1'..='9'))
+                if (c as usize - '1' as usize) < ctx.diary.get_saved_searches().len() =>
+            {
+                let index = c as usize - '1' as usize;
+                Transition::Push(Box::new(SearchResultsScreen::saved(ctx, index)))
+            }
+            _ => Transition::Stay,
+        }
+    }
+
+    /// The viewer hands back the entry it showed last, to select here. A
+    /// submitted date filter replaces the current one; an empty one clears it.
+    fn resume(&mut self, outcome: Option<Outcome>, ctx: &mut Context) -> Transition {
+        match outcome {
+            Some(Outcome::Viewed(id)) => self.reselect = Some(id),
+            Some(Outcome::Confirmed(yes)) => {
+                if let Some(id) = self.deleting.take().filter(|_| yes) {
+                    ctx.diary.delete_entry(id);
+                    ctx.notify("Entry deleted");
+                }
+                if let Some(index) = self.deleting_search.take().filter(|_| yes) {
+                    ctx.diary.delete_saved_search(index);
+                    ctx.notify("Saved search deleted");
+                }
+            }
+            Some(Outcome::Input(expression)) => {
+                ctx.date_filter = (!expression.is_empty()).then_some(expression);
+            }
+            None => {}
+        }
+        Transition::Stay
+    }
+}
+
+fn selected_entry<'a>(rows: &[ListRow<'a>], selected: Option<usize>) -> Option<&'a DiaryEntry> {
+    match selected.map(|i| &rows[i]) {
+        Some(ListRow::Entry(entry)) => Some(*entry),
+        _ => None,
+    }
+}
+
+/// The current daily streak, if there is one, and the entries written this
+/// week against the weekly goal, if one is set.
+fn habit_progress(ctx: &Context) -> Option<Line<'static>> {
+    let entries = ctx.diary.get_entries();
+    let today = ctx.today();
+    let mut parts = Vec::new();
+    let streak = stats::current_streak(entries, today);
+    if streak > 0 {
+        parts.push(Span::styled(
+            format!(" {}-day streak ", streak),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    if let Some(goal) = ctx.config.goals.entries_per_week {
+        let written = stats::entries_this_week(entries, today);
+        let color = if written >= goal {
+            Color::Green
+        } else {
+            Color::DarkGray
+        };
+        parts.push(Span::styled(
+            format!(" {}/{} this week ", written, goal),
+            Style::default().fg(color),
+        ));
+    }
+    (!parts.is_empty()).then(|| Line::from(parts))
+}
+
+/// The entries from this day in other years, with the one at `selected`
+/// highlighted while the panel has the keys.
+fn render_on_this_day(f: &mut Frame, area: Rect, memories: &[Memory], selected: Option<usize>) {
+    let items: Vec<ListItem> = memories
+        .iter()
+        .map(|memory| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{:<12}", memory.when),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::raw(memory.entry.title().to_string()),
+            ]))
+        })
+        .collect();
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .title("On this day")
+        .title_bottom(Line::styled(
+            "Tab: Switch, h: Hide",
+            Style::default().fg(Color::DarkGray),
+        ));
+    if selected.is_some() {
+        block = block.border_style(Style::default().fg(Color::Cyan));
+    }
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+        .highlight_symbol("> ");
+    f.render_stateful_widget(
+        list,
+        area,
+        &mut ListState::default().with_selected(selected),
+    );
+}
+
+/// The selected entry, with its content rendered as the viewer shows it.
+fn render_entry_preview(
+    f: &mut Frame,
+    area: Rect,
+    preview: Option<(&DiaryEntry, Vec<Line<'static>>)>,
+) {
+    let Some((entry, content)) = preview else {
+        let empty = Paragraph::new("Nothing to show. Press w to write an entry.")
+            .style(Style::default().fg(Color::DarkGray))
+            .block(Block::default().borders(Borders::ALL).title("Preview"));
+        f.render_widget(empty, area);
+        return;
+    };
+
+    let mut details = Vec::new();
+    if !entry.tags.is_empty() {
+        details.push(format!("Tags: {}", entry.tags.join(", ")));
+    }
+    if let Some(mood) = entry.mood {
+        details.push(mood_stars(mood));
+    }
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .title(entry.timestamp.format("%Y-%m-%d %H:%M").to_string());
+    if !details.is_empty() {
+        block = block.title_bottom(Line::styled(
+            details.join("  "),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    let preview = Paragraph::new(content)
+        .block(block)
+        .wrap(Wrap { trim: false });
+    f.render_widget(preview, area);
+}
@@ -0,0 +1,289 @@
+mod calendar;
+mod dialogs;
+mod editor;
+mod main_screen;
+mod prompts;
+mod search;
+mod stats;
+mod tasks;
+mod viewer;
+mod year;
+
+pub use dialogs::RecoverDraftsScreen;
+pub use main_screen::MainScreen;
+
+use editor::EditorScreen;
+
+use crate::app::{Context, Transition};
+use crate::diary_entry::DiaryEntry;
+use crate::list_order::{ListOrder, ListRow, ListScreen};
+use chrono::NaiveDate;
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
+    Frame,
+};
+use std::collections::HashMap;
+
+/// The layout every screen uses: a title, the body and a line of
+/// instructions, with a margin around them. Returns the three areas.
+fn screen_layout(area: Rect, body: Constraint) -> [Rect; 3] {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([Constraint::Length(3), body, Constraint::Length(3)].as_ref())
+        .split(area);
+    [chunks[0], chunks[1], chunks[2]]
+}
+
+fn render_title(f: &mut Frame, area: Rect, title: &str) {
+    let title = Paragraph::new(title.to_string())
+        .style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .alignment(Alignment::Center);
+    f.render_widget(title, area);
+}
+
+/// The key hints along the bottom, each line wrapped if need be.
+fn render_instructions<'a>(f: &mut Frame, area: Rect, instructions: impl Into<Text<'a>>) {
+    let instructions = Paragraph::new(instructions.into())
+        .style(Style::default().fg(Color::Yellow))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(instructions, area);
+}
+
+/// A box of `width` by `height` in the middle of `area`, cleared for a popup.
+fn popup_area(f: &mut Frame, area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    f.render_widget(Clear, popup);
+    popup
+}
+
+/// A bordered input box; an error turns the border red and is shown along the
+/// bottom edge.
+fn input_block<'a>(label: &'a str, error: Option<&str>) -> Block<'a> {
+    let block = Block::default().borders(Borders::ALL).title(label);
+    match error {
+        Some(error) => block
+            .border_style(Style::default().fg(Color::Red))
+            .title_bottom(Line::styled(
+                format!(" {} ", error),
+                Style::default().fg(Color::Red),
+            )),
+        None => block,
+    }
+}
+
+/// `mood` as five stars. A hand-edited diary may hold moods above five;
+/// they show as five.
+fn mood_stars(mood: u8) -> String {
+    let mood = mood.min(5) as usize;
+    "★".repeat(mood) + &"☆".repeat(5 - mood)
+}
+
+/// The two-line item used for an entry in the entry list: its title, then
+/// when it was written, its tags and mood.
+fn entry_list_item(entry: &DiaryEntry) -> ListItem<'static> {
+    let mut details = entry.timestamp.format("%Y-%m-%d %H:%M").to_string();
+    if !entry.tags.is_empty() {
+        details += &format!(" · {}", entry.tags.join(", "));
+    }
+    if let Some(mood) = entry.mood {
+        details += &format!(" {}", mood_stars(mood));
+    }
+
+    ListItem::new(vec![
+        Line::from(Span::raw(entry.title().to_string())),
+        Line::styled(details, Style::default().fg(Color::DarkGray)),
+    ])
+}
+
+/// Turns arranged rows into list items, rendering group headers in between
+/// the entries.
+fn list_items<'a>(
+    rows: &[ListRow],
+    entry_item: impl Fn(&DiaryEntry) -> ListItem<'a>,
+) -> Vec<ListItem<'a>> {
+    rows.iter()
+        .map(|row| match row {
+            ListRow::Header(header) => ListItem::new(Line::styled(
+                format!("── {} ──", header),
+                Style::default()
+                    .fg(Color::Magenta)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            ListRow::Entry(entry) => entry_item(entry),
+        })
+        .collect()
+}
+
+/// The entry row to select for a requested row index: `index` itself if it is
+/// an entry, otherwise the nearest entry after it (or before it, at the end of
+/// the list). `None` if there are no entries.
+fn entry_row(rows: &[ListRow], index: usize) -> Option<usize> {
+    let is_entry = |i: &usize| matches!(rows[*i], ListRow::Entry(_));
+    let index = index.min(rows.len().saturating_sub(1));
+    (index..rows.len())
+        .find(is_entry)
+        .or_else(|| (0..index).rev().find(is_entry))
+}
+
+/// Moves the selection to the next or previous entry row, skipping headers.
+/// Stays put at either end of the list.
+fn step_entry_row(rows: &[ListRow], selected: Option<usize>, forward: bool) -> usize {
+    let Some(selected) = selected else { return 0 };
+    let is_entry = |i: &usize| matches!(rows[*i], ListRow::Entry(_));
+    let next = if forward {
+        (selected + 1..rows.len()).find(is_entry)
+    } else {
+        (0..selected).rev().find(is_entry)
+    };
+    next.unwrap_or(selected)
+}
+
+fn selected_entry_id(rows: &[ListRow], selected: Option<usize>) -> Option<usize> {
+    match selected.map(|i| &rows[i]) {
+        Some(ListRow::Entry(entry)) => Some(entry.id),
+        _ => None,
+    }
+}
+
+/// The row of entry `id`, or 0 if it isn't listed.
+fn row_of(rows: &[ListRow], id: usize) -> usize {
+    rows.iter()
+        .position(|row| matches!(row, ListRow::Entry(e) if e.id == id))
+        .unwrap_or(0)
+}
+
+/// The entries in a list, in order. An entry listed in several groups
+/// appears once, where it is first listed.
+fn entry_ids(rows: &[ListRow]) -> Vec<usize> {
+    let mut ids: Vec<usize> = Vec::new();
+    for row in rows {
+        if let ListRow::Entry(entry) = row {
+            if !ids.contains(&entry.id) {
+                ids.push(entry.id);
+            }
+        }
+    }
+    ids
+}
+
+/// Shades for days with more and more writing, from a little to the most.
+const HEAT_COLORS: [Color; 4] = [
+    Color::Indexed(22),
+    Color::Indexed(28),
+    Color::Indexed(34),
+    Color::Indexed(40),
+];
+
+/// Which of the [`HEAT_COLORS`] a day with `value` gets, where `max` is the
+/// most of any day shown. `None` for a day without any.
+fn heat_color(value: usize, max: usize) -> Option<Color> {
+    if value == 0 {
+        return None;
+    }
+    let level = (value * HEAT_COLORS.len()).div_ceil(max.max(value));
+    Some(HEAT_COLORS[level.clamp(1, HEAT_COLORS.len()) - 1])
+}
+
+/// The entries written on each day, oldest first.
+fn entries_by_day(entries: &[DiaryEntry]) -> HashMap<NaiveDate, Vec<&DiaryEntry>> {
+    let mut days: HashMap<NaiveDate, Vec<&DiaryEntry>> = HashMap::new();
+    for entry in entries {
+        days.entry(entry.timestamp.date_naive())
+            .or_default()
+            .push(entry);
+    }
+    for entries in days.values_mut() {
+        entries.sort_by_key(|e| e.timestamp);
+    }
+    days
+}
+
+/// The editor for a new entry written for `day`, which can't be after
+/// `today`.
+fn write_on(ctx: &Context, day: NaiveDate, today: NaiveDate) -> Transition {
+    if day > today {
+        return Transition::Stay;
+    }
+    let editor = EditorScreen::new_entry(ctx, None);
+    if day == today {
+        Transition::Push(Box::new(editor))
+    } else {
+        Transition::Push(Box::new(editor.backdated_to(day)))
+    }
+}
+
+/// The entries written on `day`, with their times and lengths, or a note
+/// that there are none. Only days up to `today` can still be written for.
+fn render_day(
+    f: &mut Frame,
+    area: Rect,
+    day: NaiveDate,
+    entries: &[&DiaryEntry],
+    today: NaiveDate,
+) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(day.format("%A, %-d %B %Y").to_string());
+    if entries.is_empty() {
+        let note = if day > today {
+            "Nothing yet."
+        } else {
+            "No entries. Press Enter to write one."
+        };
+        let empty = Paragraph::new(note)
+            .style(Style::default().fg(Color::DarkGray))
+            .block(block);
+        f.render_widget(empty, area);
+        return;
+    }
+    let items: Vec<ListItem> = entries
+        .iter()
+        .map(|entry| {
+            ListItem::new(vec![
+                Line::raw(format!(
+                    "{} {}",
+                    entry.timestamp.format("%H:%M"),
+                    entry.title()
+                )),
+                Line::styled(
+                    format!("      {} words", entry.word_count()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ])
+        })
+        .collect();
+    f.render_widget(List::new(items).block(block), area);
+}
+
+/// Steps the sorting (`o`) or the grouping (`g`) of the list on `screen`.
+fn cycle_list_order(ctx: &mut Context, screen: ListScreen, key: char) {
+    let order = ctx.diary.get_list_order(screen);
+    let order = if key == 'o' {
+        ListOrder {
+            sort: order.sort.next(),
+            ..order
+        }
+    } else {
+        ListOrder {
+            grouping: order.grouping.next(),
+            ..order
+        }
+    };
+    ctx.diary.set_list_order(screen, order);
+}
@@ -0,0 +1,155 @@
+use super::editor::EditorScreen;
+use super::viewer::ViewerScreen;
+use super::{entry_list_item, render_instructions, render_title, screen_layout};
+use crate::app::{AppEvent, Context, Screen, Transition};
+use crate::diary_entry::DiaryEntry;
+use crate::keymap::{Command, Movement};
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+/// The prompts in the library, with the entries answering the selected one
+/// beside them, oldest first. Enter reads the answers; w writes another.
+#[derive(Default)]
+pub struct PromptsScreen {
+    selected_index: usize,
+}
+
+/// Every prompt with its category: the library's, then any only found on
+/// entries, such as ones since removed from `diary_prompts.json`.
+fn all_prompts(ctx: &Context) -> Vec<(String, String)> {
+    let mut prompts: Vec<(String, String)> = ctx
+        .prompts
+        .get_prompts()
+        .iter()
+        .map(|p| (p.category.clone(), p.text.clone()))
+        .collect();
+    for entry in ctx.diary.get_entries() {
+        if let Some(prompt) = &entry.prompt {
+            if !prompts.iter().any(|(_, text)| text == prompt) {
+                prompts.push(("other".to_string(), prompt.clone()));
+            }
+        }
+    }
+    prompts
+}
+
+/// The entries answering `prompt`, oldest first.
+fn answers<'a>(ctx: &'a Context, prompt: &str) -> Vec<&'a DiaryEntry> {
+    let mut answers: Vec<&DiaryEntry> = ctx
+        .diary
+        .get_entries()
+        .iter()
+        .filter(|e| e.prompt.as_deref() == Some(prompt))
+        .collect();
+    answers.sort_by_key(|e| e.timestamp);
+    answers
+}
+
+impl Screen for PromptsScreen {
+    fn render(&mut self, f: &mut Frame, ctx: &Context) {
+        let prompts = all_prompts(ctx);
+        self.selected_index = self.selected_index.min(prompts.len().saturating_sub(1));
+
+        let chunks = screen_layout(f.area(), Constraint::Min(10));
+        render_title(f, chunks[0], "Writing Prompts");
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
+            .split(chunks[1]);
+
+        let items: Vec<ListItem> = prompts
+            .iter()
+            .map(|(category, text)| {
+                let count = answers(ctx, text).len();
+                ListItem::new(vec![
+                    Line::raw(text.clone()),
+                    Line::styled(
+                        format!(
+                            "{} · {} answer{}",
+                            category,
+                            count,
+                            if count == 1 { "" } else { "s" }
+                        ),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ])
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title("Prompts"))
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+            .highlight_symbol("> ");
+        f.render_stateful_widget(
+            list,
+            columns[0],
+            &mut ListState::default().with_selected(Some(self.selected_index)),
+        );
+
+        let block = Block::default().borders(Borders::ALL).title("Answers");
+        let answered = prompts
+            .get(self.selected_index)
+            .map(|(_, text)| answers(ctx, text))
+            .unwrap_or_default();
+        if answered.is_empty() {
+            let empty = Paragraph::new("Not answered yet. Press w to write about it.")
+                .style(Style::default().fg(Color::DarkGray))
+                .block(block);
+            f.render_widget(empty, columns[1]);
+        } else {
+            let items: Vec<ListItem> = answered.iter().map(|e| entry_list_item(e)).collect();
+            f.render_widget(List::new(items).block(block), columns[1]);
+        }
+
+        let key = |k: &'static str| Span::styled(k, Style::default().add_modifier(Modifier::BOLD));
+        render_instructions(
+            f,
+            chunks[2],
+            Line::from(vec![
+                key("Up/Down"),
+                Span::raw(": Navigate, "),
+                key("Enter"),
+                Span::raw(": Read the answers, "),
+                key("w"),
+                Span::raw(": Answer it, "),
+                key("Esc"),
+                Span::raw(": Back"),
+            ]),
+        );
+    }
+
+    fn handle_event(&mut self, event: AppEvent, ctx: &mut Context) -> Transition {
+        let AppEvent::Key(key) = event else {
+            return Transition::Stay;
+        };
+        let prompts = all_prompts(ctx);
+        let Some((_, prompt)) = prompts.get(self.selected_index) else {
+            return Transition::Pop(None);
+        };
+        match ctx.keymap.command(key) {
+            Some(Command::Move(Movement::Up)) => {
+                self.selected_index = self.selected_index.saturating_sub(1);
+            }
+            Some(Command::Move(Movement::Down)) => {
+                self.selected_index = (self.selected_index + 1).min(prompts.len() - 1);
+            }
+            Some(Command::Confirm) => {
+                let ids: Vec<usize> = answers(ctx, prompt).iter().map(|e| e.id).collect();
+                if let Some(&first) = ids.first() {
+                    return Transition::Push(Box::new(ViewerScreen::new(ctx, ids, first)));
+                }
+            }
+            Some(Command::Char('w')) => {
+                let editor = EditorScreen::new_entry(ctx, None).answering(prompt);
+                return Transition::Push(Box::new(editor));
+            }
+            Some(Command::Cancel | Command::Char('q')) => return Transition::Pop(None),
+            _ => {}
+        }
+        Transition::Stay
+    }
+}
@@ -0,0 +1,317 @@
+use super::dialogs::PromptScreen;
+use super::viewer::ViewerScreen;
+use super::{
+    cycle_list_order, entry_ids, entry_row, input_block, list_items, render_instructions,
+    render_title, row_of, screen_layout, selected_entry_id, step_entry_row,
+};
+use crate::app::{AppEvent, Context, Outcome, Screen, Transition};
+use crate::diary_entry::DiaryEntry;
+use crate::keymap::{Command, Movement};
+use crate::list_order::{ListRow, ListScreen};
+use crate::search::{CompiledSearch, SavedSearch, SearchMode, SearchQuery};
+use crate::text_area::TextArea;
+use ratatui::{
+    layout::Constraint,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState},
+    Frame,
+};
+use regex::Regex;
+
+/// Colours used for capture groups 1, 2, ... in search results; the whole match
+/// is always shown in yellow.
+const CAPTURE_COLORS: [Color; 4] = [Color::Cyan, Color::Magenta, Color::Green, Color::LightRed];
+
+/// Asks for a search query, then shows its results in its place.
+pub struct SearchQueryScreen {
+    input: TextArea,
+    mode: SearchMode,
+}
+
+impl Default for SearchQueryScreen {
+    fn default() -> Self {
+        SearchQueryScreen {
+            input: TextArea::single_line(""),
+            mode: SearchMode::Plain,
+        }
+    }
+}
+
+impl SearchQueryScreen {
+    fn query(&self) -> SearchQuery {
+        SearchQuery {
+            text: self.input.text(),
+            mode: self.mode,
+        }
+    }
+}
+
+impl Screen for SearchQueryScreen {
+    fn render(&mut self, f: &mut Frame, _ctx: &Context) {
+        let error = self.query().compile().err();
+        let chunks = screen_layout(f.area(), Constraint::Length(3));
+        render_title(f, chunks[0], "Search Entries");
+
+        let mode_label = match self.mode {
+            SearchMode::Plain => "Search Query",
+            SearchMode::Regex => "Search Query (regex)",
+        };
+        self.input.render(
+            f,
+            chunks[1],
+            input_block(mode_label, error.as_deref()),
+            true,
+        );
+
+        render_instructions(
+            f,
+            chunks[2],
+            "Enter: Submit, Tab: Toggle regex, Esc: Cancel",
+        );
+    }
+
+    fn handle_event(&mut self, event: AppEvent, ctx: &mut Context) -> Transition {
+        let AppEvent::Key(key) = event else {
+            return Transition::Stay;
+        };
+        match ctx.keymap.command(key) {
+            Some(Command::Confirm) => {
+                let query = self.query();
+                if let Ok(search) = query.compile() {
+                    let results = SearchResultsScreen::new("Search Results", query, search, None);
+                    return Transition::Replace(Box::new(results));
+                }
+            }
+            Some(Command::NextField) => {
+                self.mode = match self.mode {
+                    SearchMode::Plain => SearchMode::Regex,
+                    SearchMode::Regex => SearchMode::Plain,
+                };
+            }
+            Some(Command::Cancel) => return Transition::Pop(None),
+            Some(command) => {
+                self.input.apply(command, &mut ctx.kill_ring);
+            }
+            None => {}
+        }
+        Transition::Stay
+    }
+}
+
+/// Shows the results of a search. `s` saves an ad-hoc search under a name
+/// and `x` removes a saved one.
+pub struct SearchResultsScreen {
+    title: String,
+    query: SearchQuery,
+    search: CompiledSearch,
+    /// The index of the saved search shown, if it is one.
+    saved: Option<usize>,
+    selected_index: usize,
+    /// An entry to select once the rows are next arranged.
+    reselect: Option<usize>,
+}
+
+impl SearchResultsScreen {
+    fn new(title: &str, query: SearchQuery, search: CompiledSearch, saved: Option<usize>) -> Self {
+        SearchResultsScreen {
+            title: title.to_string(),
+            query,
+            search,
+            saved,
+            selected_index: 0,
+            reselect: None,
+        }
+    }
+
+    /// The results of the saved search at `index`.
+    pub fn saved(ctx: &Context, index: usize) -> Self {
+        let saved = ctx.diary.get_saved_searches()[index].clone();
+        // A query saved by an older version may no longer parse; show it
+        // as empty rather than failing, so it can still be deleted.
+        let search = saved
+            .query
+            .compile()
+            .unwrap_or_else(|_| CompiledSearch::nothing());
+        SearchResultsScreen::new(&saved.name, saved.query, search, Some(index))
+    }
+
+    fn selected(&mut self, rows: &[ListRow]) -> Option<usize> {
+        if let Some(id) = self.reselect.take() {
+            self.selected_index = row_of(rows, id);
+        }
+        entry_row(rows, self.selected_index)
+    }
+}
+
+impl Screen for SearchResultsScreen {
+    fn render(&mut self, f: &mut Frame, ctx: &Context) {
+        let results = ctx.diary.search(&self.search);
+        let order = ctx.diary.get_list_order(ListScreen::Search);
+        let rows = order.arrange(results.iter().collect());
+        let selected = self.selected(&rows);
+
+        let chunks = screen_layout(f.area(), Constraint::Min(10));
+        render_title(f, chunks[0], &self.title);
+
+        let items = list_items(&rows, |e| search_result_item(&self.search, e));
+        let results_list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(order.title(&format!("Results: {}", results.len()))),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+            .highlight_symbol("> ");
+        f.render_stateful_widget(
+            results_list,
+            chunks[1],
+            &mut ListState::default().with_selected(selected),
+        );
+
+        render_instructions(
+            f,
+            chunks[2],
+            if self.saved.is_some() {
+                "Up/Down: Navigate, Enter: View full entry, o/g: Sort/Group, x: Delete saved search, Esc: Back"
+            } else {
+                "Up/Down: Navigate, Enter: View full entry, o/g: Sort/Group, s: Save search, Esc: Back"
+            },
+        );
+    }
+
+    fn handle_event(&mut self, event: AppEvent, ctx: &mut Context) -> Transition {
+        let AppEvent::Key(key) = event else {
+            return Transition::Stay;
+        };
+        let results = ctx.diary.search(&self.search);
+        let rows = ctx
+            .diary
+            .get_list_order(ListScreen::Search)
+            .arrange(results.iter().collect());
+        let selected = self.selected(&rows);
+        match ctx.keymap.command(key) {
+            Some(Command::Move(Movement::Up)) => {
+                self.selected_index = step_entry_row(&rows, selected, false)
+            }
+            Some(Command::Move(Movement::Down)) => {
+                self.selected_index = step_entry_row(&rows, selected, true)
+            }
+            Some(Command::Confirm) => {
+                if let Some(id) = selected_entry_id(&rows, selected) {
+                    let viewer = ViewerScreen::new(ctx, entry_ids(&rows), id);
+                    return Transition::Push(Box::new(viewer));
+                }
+            }
+            Some(Command::Char(c @ ('o' | 'g'))) => {
+                self.reselect = selected_entry_id(&rows, selected);
+                cycle_list_order(ctx, ListScreen::Search, c);
+            }
+            Some(Command::Char('s')) if self.saved.is_none() => {
+                let prompt = PromptScreen::new("Save Search", "Name", "", |_| Ok(()));
+                return Transition::Push(Box::new(prompt));
+            }
+            Some(Command::Char('x')) => {
+                if let Some(index) = self.saved {
+                    ctx.diary.delete_saved_search(index);
+                    ctx.notify("Saved search deleted");
+                    return Transition::Pop(None);
+                }
+            }
+            Some(Command::Cancel) => return Transition::Pop(None),
+            _ => {}
+        }
+        Transition::Stay
+    }
+
+    /// Back from the viewer, or from naming the search to save it.
+    fn resume(&mut self, outcome: Option<Outcome>, ctx: &mut Context) -> Transition {
+        match outcome {
+            Some(Outcome::Viewed(id)) => self.reselect = Some(id),
+            Some(Outcome::Input(name)) if !name.is_empty() => {
+                ctx.diary.add_saved_search(SavedSearch {
+                    name,
+                    query: self.query.clone(),
+                });
+                ctx.notify("Search saved");
+                return Transition::Pop(None);
+            }
+            _ => {}
+        }
+        Transition::Stay
+    }
+}
+
+fn search_result_item(search: &CompiledSearch, entry: &DiaryEntry) -> ListItem<'static> {
+    let match_count = search.count_matches(&entry.content)
+        + entry
+            .tags
+            .iter()
+            .map(|t| search.count_matches(t))
+            .sum::<usize>();
+    let line = entry
+        .content
+        .lines()
+        .find(|l| search.count_matches(l) > 0)
+        .unwrap_or(entry.title());
+
+    let mut first_line = vec![Span::raw(format!(
+        "[{}] ",
+        entry.timestamp.format("%Y-%m-%d %H:%M")
+    ))];
+    first_line.extend(highlight_matches(&search.matcher, line));
+    if match_count > 0 {
+        first_line.push(Span::styled(
+            format!(
+                " ({} match{})",
+                match_count,
+                if match_count == 1 { "" } else { "es" }
+            ),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    let mut tags_line = vec![Span::raw("Tags: ")];
+    tags_line.extend(highlight_matches(&search.matcher, &entry.tags.join(", ")));
+
+    ListItem::new(vec![Line::from(first_line), Line::from(tags_line)])
+}
+
+/// Splits `text` into spans, highlighting every match of `matcher` and giving
+/// each capture group its own background colour.
+fn highlight_matches(matcher: &Regex, text: &str) -> Vec<Span<'static>> {
+    let mut styles: Vec<Option<Style>> = vec![None; text.len()];
+
+    for captures in matcher.captures_iter(text) {
+        for (group, capture) in captures.iter().enumerate() {
+            let Some(capture) = capture else { continue };
+            let style = match group {
+                0 => Style::default().fg(Color::Black).bg(Color::Yellow),
+                n => Style::default()
+                    .fg(Color::Black)
+                    .bg(CAPTURE_COLORS[(n - 1) % CAPTURE_COLORS.len()]),
+            };
+            for slot in &mut styles[capture.range()] {
+                *slot = Some(style);
+            }
+        }
+    }
+
+    let mut spans = Vec::new();
+    if text.is_empty() {
+        return spans;
+    }
+    let mut start = 0;
+    let boundaries = text.char_indices().map(|(i, _)| i).skip(1);
+    for i in boundaries.chain([text.len()]) {
+        if i == text.len() || styles[i] != styles[start] {
+            let chunk = text[start..i].to_string();
+            spans.push(match styles[start] {
+                Some(style) => Span::styled(chunk, style),
+                None => Span::raw(chunk),
+            });
+            start = i;
+        }
+    }
+    spans
+}
@@ -0,0 +1,183 @@
+use super::{render_instructions, render_title, screen_layout};
+use crate::app::{AppEvent, Context, Screen, Transition};
+use crate::keymap::Command;
+use crate::stats::{Stats, TREND_MONTHS};
+use chrono::Months;
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Bar, BarChart, BarGroup, Block, Borders, List, ListItem, Paragraph},
+    Frame,
+};
+
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Figures about the whole diary: how much, how regularly, when and about
+/// what it has been written.
+pub struct StatsScreen;
+
+impl Screen for StatsScreen {
+    fn render(&mut self, f: &mut Frame, ctx: &Context) {
+        let today = ctx.today();
+        let stats = Stats::compute(ctx.diary.get_entries(), today);
+
+        let chunks = screen_layout(f.area(), Constraint::Min(10));
+        render_title(f, chunks[0], "Statistics");
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Length(5),
+                    Constraint::Min(8),
+                    Constraint::Length(8),
+                ]
+                .as_ref(),
+            )
+            .split(chunks[1]);
+        let middle = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+            .split(rows[1]);
+
+        let bold = |text: String| Span::styled(text, Style::default().add_modifier(Modifier::BOLD));
+        let days = |n: usize| format!("{} day{}", n, if n == 1 { "" } else { "s" });
+        let overview = vec![
+            Line::from(vec![
+                bold(stats.entries.to_string()),
+                Span::raw(" entries, "),
+                bold(stats.words.to_string()),
+                Span::raw(" words, "),
+                bold(stats.average_words().to_string()),
+                Span::raw(" words per entry on average"),
+            ]),
+            Line::from(vec![
+                Span::raw("Current streak: "),
+                bold(days(stats.current_streak)),
+                Span::raw(", longest: "),
+                bold(days(stats.longest_streak)),
+            ]),
+            Line::from(vec![
+                Span::raw("Words this week: "),
+                bold(stats.words_this_week.to_string()),
+                Span::raw(", last week: "),
+                bold(stats.words_last_week.to_string()),
+                week_change(stats.words_this_week, stats.words_last_week),
+            ]),
+        ];
+        let overview = Paragraph::new(overview)
+            .block(Block::default().borders(Borders::ALL).title("Overview"));
+        f.render_widget(overview, rows[0]);
+
+        let weekdays: Vec<Bar> = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]
+            .iter()
+            .zip(stats.by_weekday)
+            .map(|(name, count)| Bar::default().value(count as u64).label(Line::raw(*name)))
+            .collect();
+        let weekday_chart = BarChart::default()
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Entries by weekday"),
+            )
+            .data(BarGroup::default().bars(&weekdays))
+            .bar_width(3)
+            .bar_style(Style::default().fg(Color::Cyan))
+            .value_style(Style::default().fg(Color::Black).bg(Color::Cyan));
+        f.render_widget(weekday_chart, middle[0]);
+
+        let this_month = today.format("%b").to_string();
+        let first_month = (today - Months::new(TREND_MONTHS as u32 - 1))
+            .format("%b")
+            .to_string();
+        let tags: Vec<ListItem> = if stats.top_tags.is_empty() {
+            vec![ListItem::new(Line::styled(
+                "No tags yet.",
+                Style::default().fg(Color::DarkGray),
+            ))]
+        } else {
+            stats
+                .top_tags
+                .iter()
+                .map(|trend| {
+                    ListItem::new(Line::from(vec![
+                        Span::raw(format!("{:<16.16} {:>4}  ", trend.tag, trend.count)),
+                        Span::styled(sparkline(&trend.by_month), Style::default().fg(Color::Cyan)),
+                    ]))
+                })
+                .collect()
+        };
+        let tag_list = List::new(tags).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Top tags ({}–{})", first_month, this_month)),
+        );
+        f.render_widget(tag_list, middle[1]);
+
+        // Label every third hour; the bars are too narrow for the rest.
+        let hours: Vec<Bar> = stats
+            .by_hour
+            .iter()
+            .enumerate()
+            .map(|(hour, &count)| {
+                let label = if hour % 3 == 0 {
+                    format!("{:02}", hour)
+                } else {
+                    String::new()
+                };
+                Bar::default()
+                    .value(count as u64)
+                    .text_value(String::new())
+                    .label(Line::raw(label))
+            })
+            .collect();
+        let bar_width = ((rows[2].width.saturating_sub(2) + 1) / 24)
+            .saturating_sub(1)
+            .max(1);
+        let hour_chart = BarChart::default()
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Entries by hour"),
+            )
+            .data(BarGroup::default().bars(&hours))
+            .bar_width(bar_width)
+            .bar_style(Style::default().fg(Color::Magenta));
+        f.render_widget(hour_chart, rows[2]);
+
+        render_instructions(f, chunks[2], "Esc: Back");
+    }
+
+    fn handle_event(&mut self, event: AppEvent, ctx: &mut Context) -> Transition {
+        let AppEvent::Key(key) = event else {
+            return Transition::Stay;
+        };
+        match ctx.keymap.command(key) {
+            Some(Command::Cancel | Command::Char('q')) => Transition::Pop(None),
+            _ => Transition::Stay,
+        }
+    }
+}
+
+/// How this week's word count compares with last week's, in percent.
+fn week_change(this_week: usize, last_week: usize) -> Span<'static> {
+    if last_week == 0 {
+        return Span::raw("");
+    }
+    let change = (this_week as f64 / last_week as f64 - 1.0) * 100.0;
+    let color = if change < 0.0 {
+        Color::Red
+    } else {
+        Color::Green
+    };
+    Span::styled(format!(" ({:+.0}%)", change), Style::default().fg(color))
+}
+
+/// `counts` as a row of bars scaled to the largest of them.
+fn sparkline(counts: &[usize]) -> String {
+    let max = counts.iter().copied().max().unwrap_or(0).max(1);
+    counts
+        .iter()
+        .map(|&count| SPARKS[count * (SPARKS.len() - 1) / max])
+        .collect()
+}
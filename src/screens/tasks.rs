@@ -0,0 +1,125 @@
+use super::viewer::ViewerScreen;
+use super::{render_instructions, render_title, screen_layout};
+use crate::app::{AppEvent, Context, Screen, Transition};
+use crate::checklist::{self, Task};
+use crate::diary_entry::DiaryEntry;
+use crate::keymap::{Command, Movement};
+use ratatui::{
+    layout::Constraint,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+/// Lists the unticked checklist items of every entry, oldest entry first.
+/// Enter opens the entry an item is in; x ticks it.
+#[derive(Default)]
+pub struct OpenTasksScreen {
+    selected_index: usize,
+}
+
+/// Every unticked item, with the entry it is in.
+fn open_tasks(ctx: &Context) -> Vec<(&DiaryEntry, Task)> {
+    let mut entries: Vec<&DiaryEntry> = ctx.diary.get_entries().iter().collect();
+    entries.sort_by_key(|e| e.timestamp);
+    entries
+        .into_iter()
+        .flat_map(|entry| {
+            checklist::tasks(&entry.content)
+                .into_iter()
+                .filter(|task| !task.done)
+                .map(move |task| (entry, task))
+        })
+        .collect()
+}
+
+impl Screen for OpenTasksScreen {
+    fn render(&mut self, f: &mut Frame, ctx: &Context) {
+        let open = open_tasks(ctx);
+        self.selected_index = self.selected_index.min(open.len().saturating_sub(1));
+
+        let chunks = screen_layout(f.area(), Constraint::Min(10));
+        render_title(f, chunks[0], "Open Tasks");
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Unticked items: {}", open.len()));
+        if open.is_empty() {
+            let empty = Paragraph::new("No open tasks. Add some with - [ ] in an entry.")
+                .style(Style::default().fg(Color::DarkGray))
+                .block(block);
+            f.render_widget(empty, chunks[1]);
+        } else {
+            let items: Vec<ListItem> = open
+                .iter()
+                .map(|(entry, task)| {
+                    ListItem::new(Line::from(vec![
+                        Span::styled("[ ] ", Style::default().fg(Color::Yellow)),
+                        Span::raw(task.text.clone()),
+                        Span::styled(
+                            format!("  {} {}", entry.timestamp.format("%Y-%m-%d"), entry.title()),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                    ]))
+                })
+                .collect();
+            let list = List::new(items)
+                .block(block)
+                .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+                .highlight_symbol("> ");
+            f.render_stateful_widget(
+                list,
+                chunks[1],
+                &mut ListState::default().with_selected(Some(self.selected_index)),
+            );
+        }
+
+        render_instructions(
+            f,
+            chunks[2],
+            "Up/Down: Navigate, Enter: Open entry, x: Tick, Esc: Back",
+        );
+    }
+
+    fn handle_event(&mut self, event: AppEvent, ctx: &mut Context) -> Transition {
+        let AppEvent::Key(key) = event else {
+            return Transition::Stay;
+        };
+        let open = open_tasks(ctx);
+        let selected = open
+            .get(self.selected_index)
+            .map(|(entry, task)| ((*entry).clone(), task.line));
+        let mut ids: Vec<usize> = open.iter().map(|(e, _)| e.id).collect();
+        ids.dedup();
+
+        match ctx.keymap.command(key) {
+            Some(Command::Move(Movement::Up)) => {
+                self.selected_index = self.selected_index.saturating_sub(1)
+            }
+            // Clamped to the list when next rendered.
+            Some(Command::Move(Movement::Down)) => self.selected_index += 1,
+            Some(Command::Confirm) => {
+                if let Some((entry, _)) = selected {
+                    let viewer = ViewerScreen::new(ctx, ids, entry.id);
+                    return Transition::Push(Box::new(viewer));
+                }
+            }
+            Some(Command::Char('x')) => {
+                if let Some((entry, line)) = selected {
+                    let now = ctx.now();
+                    ctx.diary.update_entry(
+                        DiaryEntry {
+                            content: checklist::toggle(&entry.content, line),
+                            ..entry
+                        },
+                        now,
+                    );
+                }
+            }
+            Some(Command::Cancel | Command::Char('q')) => return Transition::Pop(None),
+            _ => {}
+        }
+        Transition::Stay
+    }
+}
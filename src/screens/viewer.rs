@@ -0,0 +1,341 @@
+use super::{render_instructions, render_title, screen_layout};
+use crate::app::{AppEvent, Context, Outcome, Screen, Transition};
+use crate::checklist::{self, Task};
+use crate::diary_entry::DiaryEntry;
+use crate::keymap::{Command, Movement};
+use crate::markdown::{self, Rendered};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{
+        Block, Borders, List, ListItem, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState,
+        Wrap,
+    },
+    Frame,
+};
+
+/// Number of similar entries listed next to an entry; each gets a digit key.
+const RELATED_ENTRIES_SHOWN: usize = 5;
+
+/// Shows an entry for reading. `ids` is the list it was opened from, which
+/// n/p step through. Each entry reopens where it was left, by the line of
+/// its content at the top, so the place holds whatever the terminal's width.
+/// Closes with the entry shown last.
+pub struct ViewerScreen {
+    ids: Vec<usize>,
+    index: usize,
+    entry: DiaryEntry,
+    related: Vec<(DiaryEntry, f64)>,
+    rendered: Rendered,
+    tasks: Vec<Task>,
+    selected_task: Option<usize>,
+    /// Whether to scroll the selected task into view on the next render.
+    reveal_task: bool,
+    scroll: usize,
+    /// The content line to scroll to the top on the next render, once the
+    /// rows it wraps to are known.
+    restore: Option<usize>,
+    /// Set while rendering, from the size of the content area.
+    page: usize,
+    max_scroll: usize,
+    width: u16,
+    /// The first row of each line shown, with the content line it is from.
+    rows: Vec<(usize, usize)>,
+}
+
+impl ViewerScreen {
+    pub fn new(ctx: &Context, ids: Vec<usize>, id: usize) -> Self {
+        let index = ids.iter().position(|&i| i == id).unwrap_or(0);
+        let mut viewer = ViewerScreen {
+            ids,
+            index,
+            entry: DiaryEntry::new(id, String::new(), Vec::new()),
+            related: Vec::new(),
+            rendered: Rendered::default(),
+            tasks: Vec::new(),
+            selected_task: None,
+            reveal_task: false,
+            scroll: 0,
+            restore: None,
+            page: 1,
+            max_scroll: 0,
+            width: 0,
+            rows: Vec::new(),
+        };
+        viewer.load(ctx, id);
+        viewer
+    }
+
+    /// Shows entry `id`, from where it was last left.
+    fn load(&mut self, ctx: &Context, id: usize) {
+        if let Some(entry) = ctx.diary.get_entries().iter().find(|e| e.id == id) {
+            self.entry = entry.clone();
+        }
+        self.related = ctx
+            .diary
+            .related_entries(id, RELATED_ENTRIES_SHOWN)
+            .into_iter()
+            .map(|(e, score)| (e.clone(), score))
+            .collect();
+        self.rendered = markdown::render_mapped(&self.entry.content);
+        self.tasks = checklist::tasks(&self.entry.content);
+        self.selected_task = self.selected_task.filter(|&i| i < self.tasks.len());
+        self.scroll = 0;
+        self.rows.clear();
+        self.restore = Some(ctx.diary.get_reading_position(id));
+    }
+
+    /// The content line of the row at the top, as of the last render.
+    fn top_line(&self) -> usize {
+        if let Some(line) = self.restore {
+            return line;
+        }
+        let shown = self.rows.partition_point(|&(row, _)| row <= self.scroll);
+        shown.checked_sub(1).map_or(0, |i| self.rows[i].1)
+    }
+
+    fn leave(&self, ctx: &mut Context) {
+        ctx.diary
+            .set_reading_position(self.entry.id, self.top_line());
+    }
+
+    fn step(&mut self, ctx: &mut Context, step: isize) {
+        self.leave(ctx);
+        self.index = self.index.saturating_add_signed(step);
+        self.selected_task = None;
+        self.load(ctx, self.ids[self.index]);
+    }
+}
+
+impl Screen for ViewerScreen {
+    fn render(&mut self, f: &mut Frame, ctx: &Context) {
+        let entry = &self.entry;
+        let chunks = screen_layout(f.area(), Constraint::Min(10));
+
+        let position = if self.ids.len() > 1 {
+            format!(" ({} of {})", self.index + 1, self.ids.len())
+        } else {
+            String::new()
+        };
+        render_title(
+            f,
+            chunks[0],
+            &format!(
+                "Entry from {}{}",
+                entry.timestamp.format("%Y-%m-%d %H:%M"),
+                position,
+            ),
+        );
+
+        let body = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(40)].as_ref())
+            .split(chunks[1]);
+
+        let (mut lines, sources, task_line) = if ctx.raw_view {
+            let lines: Vec<Line> = entry.content.split('\n').map(Line::raw).collect();
+            let sources = (0..lines.len()).collect();
+            (
+                lines,
+                sources,
+                self.selected_task.map(|i| self.tasks[i].line),
+            )
+        } else {
+            (
+                self.rendered.lines.clone(),
+                self.rendered.source_lines.clone(),
+                self.selected_task
+                    .and_then(|i| self.rendered.task_lines.get(i).copied()),
+            )
+        };
+        if let Some(line) = task_line.and_then(|l| lines.get_mut(l)) {
+            line.style = line.style.add_modifier(Modifier::REVERSED);
+        }
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(if ctx.raw_view {
+                "Content (raw)"
+            } else {
+                "Content"
+            });
+        let inner = block.inner(body[0]);
+        // Leave the last column for the scrollbar.
+        let width = inner.width.saturating_sub(1);
+        self.page = (inner.height as usize).max(1);
+        // Keep the same line at the top when the width changes.
+        if width != self.width {
+            self.restore = Some(self.top_line());
+            self.width = width;
+        }
+        self.rows.clear();
+        let mut height = 0;
+        for (line, source) in lines.iter().zip(sources) {
+            self.rows.push((height, source));
+            height += Paragraph::new(line.clone())
+                .wrap(Wrap { trim: false })
+                .line_count(width);
+        }
+        if let Some(top) = self.restore.take() {
+            self.scroll = self
+                .rows
+                .iter()
+                .find(|&&(_, source)| source >= top)
+                .map_or(height, |&(row, _)| row);
+        }
+        if let Some(line) = task_line.filter(|_| self.reveal_task) {
+            let row = self.rows[line].0;
+            if row < self.scroll {
+                self.scroll = row;
+            } else if row >= self.scroll + self.page {
+                self.scroll = row + 1 - self.page;
+            }
+            self.reveal_task = false;
+        }
+        let content = Paragraph::new(lines).wrap(Wrap { trim: false });
+        self.max_scroll = height.saturating_sub(self.page);
+        self.scroll = self.scroll.min(self.max_scroll);
+        f.render_widget(block, body[0]);
+        f.render_widget(
+            content.scroll((self.scroll as u16, 0)),
+            Rect { width, ..inner },
+        );
+        if self.max_scroll > 0 {
+            f.render_stateful_widget(
+                Scrollbar::new(ScrollbarOrientation::VerticalRight),
+                inner,
+                &mut ScrollbarState::new(self.max_scroll).position(self.scroll),
+            );
+        }
+
+        let related_items: Vec<ListItem> = self
+            .related
+            .iter()
+            .enumerate()
+            .map(|(i, (e, score))| {
+                ListItem::new(vec![
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{} ", i + 1),
+                            Style::default().add_modifier(Modifier::BOLD),
+                        ),
+                        Span::raw(e.timestamp.format("%Y-%m-%d").to_string()),
+                        Span::styled(
+                            format!(" {:.0}%", score * 100.0),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                    ]),
+                    Line::from(Span::raw(format!("  {}", e.title()))),
+                ])
+            })
+            .collect();
+        let related_list =
+            List::new(related_items).block(Block::default().borders(Borders::ALL).title("Related"));
+        f.render_widget(related_list, body[1]);
+
+        let mut hints = vec!["j/k/PgUp/PgDn/g/G: Scroll"];
+        if self.ids.len() > 1 {
+            hints.push("n/p: Next/previous entry");
+        }
+        if !self.related.is_empty() {
+            hints.push("1-5: Open related");
+        }
+        if !self.tasks.is_empty() {
+            hints.push("t/T: Select task, x: Tick");
+        }
+        hints.push(if ctx.raw_view {
+            "r: Rendered view"
+        } else {
+            "r: Raw view"
+        });
+        hints.push("Esc: Back");
+        render_instructions(f, chunks[2], hints.join(", "));
+    }
+
+    fn handle_event(&mut self, event: AppEvent, ctx: &mut Context) -> Transition {
+        let AppEvent::Key(key) = event else {
+            return Transition::Stay;
+        };
+        match ctx.keymap.command(key) {
+            Some(Command::Move(Movement::Down) | Command::Char('j')) => {
+                self.scroll = (self.scroll + 1).min(self.max_scroll);
+            }
+            Some(Command::Move(Movement::Up) | Command::Char('k')) => {
+                self.scroll = self.scroll.saturating_sub(1);
+            }
+            Some(Command::Move(Movement::PageDown) | Command::Char(' ')) => {
+                self.scroll = (self.scroll + self.page).min(self.max_scroll);
+            }
+            Some(Command::Move(Movement::PageUp)) => {
+                self.scroll = self.scroll.saturating_sub(self.page);
+            }
+            Some(Command::Move(Movement::Start) | Command::Char('g')) => {
+                self.scroll = 0;
+            }
+            Some(Command::Move(Movement::End) | Command::Char('G')) => {
+                self.scroll = self.max_scroll;
+            }
+            Some(Command::Move(Movement::Right) | Command::Char('n'))
+                if self.index + 1 < self.ids.len() =>
+            {
+                self.step(ctx, 1);
+            }
+            Some(Command::Move(Movement::Left) | Command::Char('p')) if self.index > 0 => {
+                self.step(ctx, -1);
+            }
+            Some(Command::Char(c @ '1'..='9'))
+                if (c as usize - '1' as usize) < self.related.len() =>
+            {
+                let related_id = self.related[c as usize - '1' as usize].0.id;
+                self.leave(ctx);
+                let viewer = ViewerScreen::new(ctx, vec![related_id], related_id);
+                return Transition::Push(Box::new(viewer));
+            }
+            Some(Command::Char('r')) => {
+                self.restore = Some(self.top_line());
+                ctx.raw_view = !ctx.raw_view;
+                self.reveal_task = true;
+            }
+            Some(Command::Char(c @ ('t' | 'T'))) if !self.tasks.is_empty() => {
+                let count = self.tasks.len();
+                self.selected_task = Some(match (self.selected_task, c) {
+                    (Some(i), 't') => (i + 1) % count,
+                    (Some(i), _) => (i + count - 1) % count,
+                    (None, 't') => 0,
+                    (None, _) => count - 1,
+                });
+                self.reveal_task = true;
+            }
+            Some(Command::Char('x')) => {
+                if let Some(task) = self.selected_task.map(|i| &self.tasks[i]) {
+                    let content = checklist::toggle(&self.entry.content, task.line);
+                    self.leave(ctx);
+                    let now = ctx.now();
+                    ctx.diary.update_entry(
+                        DiaryEntry {
+                            content,
+                            ..self.entry.clone()
+                        },
+                        now,
+                    );
+                    self.load(ctx, self.entry.id);
+                }
+            }
+            Some(Command::Cancel) if self.selected_task.is_some() => {
+                self.selected_task = None;
+            }
+            Some(Command::Cancel | Command::Char('q')) => {
+                self.leave(ctx);
+                return Transition::Pop(Some(Outcome::Viewed(self.entry.id)));
+            }
+            _ => {}
+        }
+        Transition::Stay
+    }
+
+    /// Back from a related entry, which may have changed since.
+    fn resume(&mut self, _outcome: Option<Outcome>, ctx: &mut Context) -> Transition {
+        self.load(ctx, self.entry.id);
+        Transition::Stay
+    }
+}
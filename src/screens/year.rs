@@ -0,0 +1,254 @@
+use super::calendar::CalendarScreen;
+use super::viewer::ViewerScreen;
+use super::{
+    entries_by_day, heat_color, render_day, render_instructions, render_title, screen_layout,
+    write_on, HEAT_COLORS,
+};
+use crate::app::{AppEvent, Context, Screen, Transition};
+use crate::diary_entry::DiaryEntry;
+use crate::keymap::{Command, Movement};
+use chrono::{Datelike, Days, Months, NaiveDate};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Widget},
+    Frame,
+};
+use std::collections::HashMap;
+
+/// Colours for an average mood of 1 to 5 stars.
+const MOOD_COLORS: [Color; 5] = [
+    Color::Red,
+    Color::LightRed,
+    Color::Yellow,
+    Color::LightGreen,
+    Color::Green,
+];
+
+/// What the year's cells are coloured by.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Shading {
+    Mood,
+    Words,
+}
+
+/// The colour for the average mood of `entries`, rounded to whole stars.
+/// `None` if none of them has a mood.
+fn mood_color(entries: &[&DiaryEntry]) -> Option<Color> {
+    let moods: Vec<u8> = entries.iter().filter_map(|e| e.mood).collect();
+    if moods.is_empty() {
+        return None;
+    }
+    let total: usize = moods.iter().map(|&m| m as usize).sum();
+    let average = (total * 2 + moods.len()) / (moods.len() * 2);
+    Some(MOOD_COLORS[average.clamp(1, MOOD_COLORS.len()) - 1])
+}
+
+/// A whole year at a glance, one cell per day, coloured by mood or by how
+/// much was written. Enter reads the selected day's entries.
+pub struct YearScreen {
+    today: NaiveDate,
+    selected: NaiveDate,
+    shading: Shading,
+}
+
+impl YearScreen {
+    pub fn new(today: NaiveDate) -> Self {
+        YearScreen {
+            today,
+            selected: today,
+            shading: Shading::Mood,
+        }
+    }
+}
+
+impl Screen for YearScreen {
+    fn render(&mut self, f: &mut Frame, ctx: &Context) {
+        let days = entries_by_day(ctx.diary.get_entries());
+        let year = self.selected.year();
+        let in_year: Vec<(&NaiveDate, &Vec<&DiaryEntry>)> =
+            days.iter().filter(|(day, _)| day.year() == year).collect();
+        let words =
+            |entries: &[&DiaryEntry]| -> usize { entries.iter().map(|e| e.word_count()).sum() };
+        let max = in_year.iter().map(|(_, e)| words(e)).max().unwrap_or(0);
+        let colors: HashMap<NaiveDate, Color> = in_year
+            .iter()
+            .filter_map(|(day, entries)| {
+                let color = match self.shading {
+                    Shading::Mood => mood_color(entries),
+                    Shading::Words => heat_color(words(entries), max),
+                };
+                color.map(|c| (**day, c))
+            })
+            .collect();
+
+        let chunks = screen_layout(f.area(), Constraint::Min(10));
+        render_title(f, chunks[0], "Year in Pixels");
+        let body = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(10), Constraint::Min(0)].as_ref())
+            .split(chunks[1]);
+
+        let (low, swatches, high) = match self.shading {
+            Shading::Mood => (" Low ", &MOOD_COLORS[..], " High mood "),
+            Shading::Words => (" Less ", &HEAT_COLORS[..], " More words "),
+        };
+        let mut legend = vec![Span::raw(low)];
+        legend.extend(
+            swatches
+                .iter()
+                .map(|&color| Span::styled("■", Style::default().fg(color))),
+        );
+        legend.push(Span::raw(high));
+        let entry_count: usize = in_year.iter().map(|(_, e)| e.len()).sum();
+        let word_count: usize = in_year.iter().map(|(_, e)| words(e)).sum();
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(year.to_string())
+            .title(
+                Line::from(format!(
+                    " {} entr{}, {} words on {} day{} ",
+                    entry_count,
+                    if entry_count == 1 { "y" } else { "ies" },
+                    word_count,
+                    in_year.len(),
+                    if in_year.len() == 1 { "" } else { "s" }
+                ))
+                .right_aligned(),
+            )
+            .title_bottom(Line::from(legend));
+        let grid = YearGrid {
+            year,
+            selected: self.selected,
+            today: self.today,
+            colors: &colors,
+        };
+        f.render_widget(grid, block.inner(body[0]));
+        f.render_widget(block, body[0]);
+
+        let selected = days.get(&self.selected).cloned().unwrap_or_default();
+        render_day(f, body[1], self.selected, &selected, self.today);
+
+        render_instructions(
+            f,
+            chunks[2],
+            format!(
+                "Arrows: Move, PgUp/PgDn: Year, t: Today, Enter: Read or write, c: Month, m: Colour by {}, Esc: Back",
+                match self.shading {
+                    Shading::Mood => "words",
+                    Shading::Words => "mood",
+                }
+            ),
+        );
+    }
+
+    fn handle_event(&mut self, event: AppEvent, ctx: &mut Context) -> Transition {
+        let AppEvent::Key(key) = event else {
+            return Transition::Stay;
+        };
+        let selected = self.selected;
+        // Weeks run across the grid, so the days of one run down it.
+        let moved = match ctx.keymap.command(key) {
+            Some(Command::Move(Movement::Up)) => selected.checked_sub_days(Days::new(1)),
+            Some(Command::Move(Movement::Down)) => selected.checked_add_days(Days::new(1)),
+            Some(Command::Move(Movement::Left)) => selected.checked_sub_days(Days::new(7)),
+            Some(Command::Move(Movement::Right)) => selected.checked_add_days(Days::new(7)),
+            Some(Command::Move(Movement::PageUp)) => selected.checked_sub_months(Months::new(12)),
+            Some(Command::Move(Movement::PageDown)) => selected.checked_add_months(Months::new(12)),
+            Some(Command::Char('t')) => Some(self.today),
+            Some(Command::Char('m')) => {
+                self.shading = match self.shading {
+                    Shading::Mood => Shading::Words,
+                    Shading::Words => Shading::Mood,
+                };
+                None
+            }
+            Some(Command::Confirm) => {
+                let days = entries_by_day(ctx.diary.get_entries());
+                return match days.get(&selected) {
+                    Some(entries) => {
+                        let ids: Vec<usize> = entries.iter().map(|e| e.id).collect();
+                        let viewer = ViewerScreen::new(ctx, ids.clone(), ids[0]);
+                        Transition::Push(Box::new(viewer))
+                    }
+                    None => write_on(ctx, selected, self.today),
+                };
+            }
+            Some(Command::Char('c')) => {
+                let calendar = CalendarScreen::new(self.today).showing(selected);
+                return Transition::Push(Box::new(calendar));
+            }
+            Some(Command::Cancel | Command::Char('q')) => return Transition::Pop(None),
+            _ => None,
+        };
+        if let Some(day) = moved {
+            self.selected = day;
+        }
+        Transition::Stay
+    }
+}
+
+/// The days of `year` as a grid with a column for each week, Monday at the
+/// top, and the months named above the weeks they start in. Cells are two
+/// columns wide where there is room and one where there isn't.
+struct YearGrid<'a> {
+    year: i32,
+    selected: NaiveDate,
+    today: NaiveDate,
+    colors: &'a HashMap<NaiveDate, Color>,
+}
+
+impl Widget for YearGrid<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        const LABEL_WIDTH: u16 = 4;
+        let first = NaiveDate::from_ymd_opt(self.year, 1, 1).unwrap();
+        let offset = first.weekday().num_days_from_monday() as u16;
+        let days: Vec<NaiveDate> = first
+            .iter_days()
+            .take_while(|d| d.year() == self.year)
+            .collect();
+        let weeks = (offset + days.len() as u16).div_ceil(7);
+        let cell_width = if area.width >= LABEL_WIDTH + weeks * 2 {
+            2
+        } else {
+            1
+        };
+
+        let dim = Style::default().fg(Color::DarkGray);
+        for (row, name) in ["Mon", "", "Wed", "", "Fri", "", "Sun"].iter().enumerate() {
+            if 1 + (row as u16) < area.height {
+                buf.set_stringn(area.x, area.y + 1 + row as u16, name, 3, dim);
+            }
+        }
+        for day in days {
+            let index = offset + day.ordinal0() as u16;
+            let x = area.x + LABEL_WIDTH + index / 7 * cell_width;
+            let y = area.y + 1 + index % 7;
+            if x >= area.right() {
+                break;
+            }
+            if day.day() == 1 {
+                let label = day.format("%b").to_string();
+                let room = (area.right() - x) as usize;
+                buf.set_stringn(x, area.y, label, room, dim);
+            }
+            if y >= area.bottom() {
+                continue;
+            }
+
+            let (symbol, mut style) = match self.colors.get(&day) {
+                Some(&color) => ("■", Style::default().fg(color)),
+                None => ("·", dim),
+            };
+            if day == self.today {
+                style = style.add_modifier(Modifier::BOLD);
+            }
+            if day == self.selected {
+                style = style.bg(Color::White);
+            }
+            buf.set_string(x, y, symbol, style);
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use crate::date_expr::{self, DateRange};
+use crate::diary_entry::DiaryEntry;
+use chrono::{Local, NaiveDate};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SearchMode {
+    Plain,
+    Regex,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SearchQuery {
+    pub text: String,
+    pub mode: SearchMode,
+}
+
+/// A named query shown as a smart folder on the main screen. Only the query
+/// text is stored, so relative filters are re-evaluated every time it is run.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: SearchQuery,
+}
+
+/// A query that has been parsed and validated, ready to run against entries.
+pub struct CompiledSearch {
+    /// Matches the free text of the query inside entry content and tags; it is
+    /// also used to count and highlight matches in the results.
+    pub matcher: Regex,
+    tags: Vec<String>,
+    after: Option<NaiveDate>,
+    before: Option<NaiveDate>,
+    during: Option<DateRange>,
+}
+
+impl SearchQuery {
+    /// Parses the query. Plain queries understand `tag:<name>`,
+    /// `date:<expr>`, `after:<expr>` (inclusive) and `before:<expr>`
+    /// (exclusive) filters, where `<expr>` is any date expression such as
+    /// `2023-03`, `last-week` or `"3 days ago"`; the remaining words are
+    /// matched literally and case-insensitively. Regex queries are used as a
+    /// single pattern.
+    pub fn compile(&self) -> Result<CompiledSearch, String> {
+        match self.mode {
+            SearchMode::Plain => {
+                let today = Local::now().date_naive();
+                let mut words = Vec::new();
+                let mut tags = Vec::new();
+                let mut after = None;
+                let mut before = None;
+                let mut during = None;
+
+                for term in split_terms(&self.text) {
+                    if let Some(tag) = term.strip_prefix("tag:") {
+                        tags.push(tag.trim_start_matches('#').to_lowercase());
+                    } else if let Some(date) = term.strip_prefix("after:") {
+                        after = Some(date_expr::parse(date, today)?.start);
+                    } else if let Some(date) = term.strip_prefix("before:") {
+                        before = Some(date_expr::parse(date, today)?.start);
+                    } else if let Some(date) = term.strip_prefix("date:") {
+                        during = Some(date_expr::parse(date, today)?);
+                    } else {
+                        words.push(term);
+                    }
+                }
+
+                let matcher = RegexBuilder::new(&regex::escape(&words.join(" ")))
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| regex_error_summary(&e))?;
+                Ok(CompiledSearch {
+                    matcher,
+                    tags,
+                    after,
+                    before,
+                    during,
+                })
+            }
+            SearchMode::Regex => Ok(CompiledSearch {
+                matcher: Regex::new(&self.text).map_err(|e| regex_error_summary(&e))?,
+                tags: Vec::new(),
+                after: None,
+                before: None,
+                during: None,
+            }),
+        }
+    }
+}
+
+impl CompiledSearch {
+    /// A search that matches no entries.
+    pub fn nothing() -> Self {
+        CompiledSearch {
+            matcher: Regex::new(r"[^\s\S]").unwrap(),
+            tags: Vec::new(),
+            after: None,
+            before: None,
+            during: None,
+        }
+    }
+
+    pub fn matches(&self, entry: &DiaryEntry) -> bool {
+        let date = entry.timestamp.date_naive();
+
+        self.after.is_none_or(|after| date >= after)
+            && self.before.is_none_or(|before| date < before)
+            && self.during.is_none_or(|during| during.contains(date))
+            && self
+                .tags
+                .iter()
+                .all(|tag| entry.tags.iter().any(|t| t.to_lowercase() == *tag))
+            && (self.matcher.is_match(&entry.content)
+                || entry.tags.iter().any(|t| self.matcher.is_match(t)))
+    }
+
+    /// Number of non-empty matches of the free text in `text`.
+    pub fn count_matches(&self, text: &str) -> usize {
+        self.matcher
+            .find_iter(text)
+            .filter(|m| !m.as_str().is_empty())
+            .count()
+    }
+}
+
+/// Splits a query on whitespace, keeping double-quoted parts together so
+/// filters can take multi-word values (`date:"last week"`).
+fn split_terms(text: &str) -> Vec<String> {
+    let mut terms = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for c in text.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    terms.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        terms.push(current);
+    }
+    terms
+}
+
+/// Regex syntax errors span several lines (pattern, caret, message); only the
+/// message fits in the search input.
+fn regex_error_summary(error: &regex::Error) -> String {
+    let message = error.to_string();
+    message
+        .lines()
+        .last()
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
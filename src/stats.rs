@@ -0,0 +1,247 @@
+//! Figures about the writing habit, computed from the entries as they are:
+//! how much has been written, how regularly, when, and about what.
+
+use crate::date_expr;
+use crate::diary_entry::DiaryEntry;
+use chrono::{Datelike, Months, NaiveDate, Timelike};
+use std::collections::{BTreeSet, HashMap};
+
+/// How many tags [`Stats::top_tags`] lists.
+const TOP_TAGS: usize = 5;
+
+/// How many months, up to and including the current one, a tag's trend
+/// covers.
+pub const TREND_MONTHS: usize = 6;
+
+pub struct Stats {
+    pub entries: usize,
+    pub words: usize,
+    pub current_streak: usize,
+    pub longest_streak: usize,
+    /// Entries written on each day of the week, Monday first.
+    pub by_weekday: [usize; 7],
+    /// Entries written in each hour of the day.
+    pub by_hour: [usize; 24],
+    /// The most used tags, most used first.
+    pub top_tags: Vec<TagTrend>,
+    pub words_this_week: usize,
+    pub words_last_week: usize,
+}
+
+pub struct TagTrend {
+    pub tag: String,
+    pub count: usize,
+    /// Entries with the tag in each of the last [`TREND_MONTHS`] months,
+    /// oldest first.
+    pub by_month: [usize; TREND_MONTHS],
+}
+
+impl Stats {
+    pub fn compute(entries: &[DiaryEntry], today: NaiveDate) -> Self {
+        let mut by_weekday = [0; 7];
+        let mut by_hour = [0; 24];
+        for entry in entries {
+            by_weekday[entry.timestamp.weekday().num_days_from_monday() as usize] += 1;
+            by_hour[entry.timestamp.hour() as usize] += 1;
+        }
+
+        let words_in = |expression| {
+            let week = date_expr::parse(expression, today).unwrap();
+            entries
+                .iter()
+                .filter(|e| week.contains(e.timestamp.date_naive()))
+                .map(|e| e.word_count())
+                .sum()
+        };
+
+        Stats {
+            entries: entries.len(),
+            words: entries.iter().map(|e| e.word_count()).sum(),
+            current_streak: current_streak(entries, today),
+            longest_streak: longest_streak(entries),
+            by_weekday,
+            by_hour,
+            top_tags: top_tags(entries, today),
+            words_this_week: words_in("this week"),
+            words_last_week: words_in("last week"),
+        }
+    }
+
+    /// The average length of an entry in words, rounded down.
+    pub fn average_words(&self) -> usize {
+        self.words.checked_div(self.entries).unwrap_or(0)
+    }
+}
+
+/// The words written in the entries of `day`.
+pub fn words_on(entries: &[DiaryEntry], day: NaiveDate) -> usize {
+    entries
+        .iter()
+        .filter(|e| e.timestamp.date_naive() == day)
+        .map(|e| e.word_count())
+        .sum()
+}
+
+/// The entries written so far in the week of `today`.
+pub fn entries_this_week(entries: &[DiaryEntry], today: NaiveDate) -> usize {
+    let week = date_expr::parse("this week", today).unwrap();
+    entries
+        .iter()
+        .filter(|e| week.contains(e.timestamp.date_naive()))
+        .count()
+}
+
+fn days_written(entries: &[DiaryEntry]) -> BTreeSet<NaiveDate> {
+    entries.iter().map(|e| e.timestamp.date_naive()).collect()
+}
+
+/// The number of days in a row, up to today, with at least one entry. A
+/// streak that reached yesterday still counts until today is over.
+pub fn current_streak(entries: &[DiaryEntry], today: NaiveDate) -> usize {
+    let days = days_written(entries);
+    let mut day = today;
+    if !days.contains(&day) {
+        day = day.pred_opt().unwrap();
+    }
+    let mut streak = 0;
+    while days.contains(&day) {
+        streak += 1;
+        day = day.pred_opt().unwrap();
+    }
+    streak
+}
+
+/// The most days in a row that were written on.
+fn longest_streak(entries: &[DiaryEntry]) -> usize {
+    let mut longest = 0;
+    let mut streak = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for day in days_written(entries) {
+        streak = match previous {
+            Some(previous) if previous.succ_opt() == Some(day) => streak + 1,
+            _ => 1,
+        };
+        longest = longest.max(streak);
+        previous = Some(day);
+    }
+    longest
+}
+
+fn top_tags(entries: &[DiaryEntry], today: NaiveDate) -> Vec<TagTrend> {
+    let this_month = today.with_day(1).unwrap();
+    let months: Vec<NaiveDate> = (0..TREND_MONTHS as u32)
+        .rev()
+        .map(|back| this_month - Months::new(back))
+        .collect();
+
+    let mut tags: HashMap<&str, TagTrend> = HashMap::new();
+    for entry in entries {
+        let month = entry.timestamp.date_naive().with_day(1).unwrap();
+        for tag in &entry.tags {
+            let trend = tags.entry(tag).or_insert_with(|| TagTrend {
+                tag: tag.clone(),
+                count: 0,
+                by_month: [0; TREND_MONTHS],
+            });
+            trend.count += 1;
+            if let Some(i) = months.iter().position(|&m| m == month) {
+                trend.by_month[i] += 1;
+            }
+        }
+    }
+
+    let mut tags: Vec<TagTrend> = tags.into_values().collect();
+    tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+    tags.truncate(TOP_TAGS);
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn today() -> NaiveDate {
+        // A Thursday.
+        date(2024, 3, 14)
+    }
+
+    fn entry(day: NaiveDate, hour: u32, content: &str, tags: &[&str]) -> DiaryEntry {
+        DiaryEntry {
+            timestamp: Local
+                .from_local_datetime(&day.and_hms_opt(hour, 0, 0).unwrap())
+                .unwrap(),
+            ..DiaryEntry::new(
+                0,
+                content.to_string(),
+                tags.iter().map(|t| t.to_string()).collect(),
+            )
+        }
+    }
+
+    #[test]
+    fn counts_streaks_of_days() {
+        let entries: Vec<DiaryEntry> = [
+            date(2024, 3, 1),
+            date(2024, 3, 2),
+            date(2024, 3, 3),
+            date(2024, 3, 3),
+            date(2024, 3, 4),
+            date(2024, 3, 12),
+            date(2024, 3, 13),
+        ]
+        .into_iter()
+        .map(|day| entry(day, 9, "Something", &[]))
+        .collect();
+
+        let stats = Stats::compute(&entries, today());
+        assert_eq!(stats.longest_streak, 4);
+        // Not written today yet, but the streak up to yesterday still stands.
+        assert_eq!(stats.current_streak, 2);
+        assert_eq!(current_streak(&entries, date(2024, 3, 15)), 0);
+        assert_eq!(current_streak(&[], today()), 0);
+    }
+
+    #[test]
+    fn totals_words_and_compares_weeks() {
+        let entries = vec![
+            entry(date(2024, 3, 11), 8, "One two three", &[]),
+            entry(date(2024, 3, 14), 22, "Four five", &[]),
+            entry(date(2024, 3, 10), 22, "Six", &[]),
+            entry(date(2024, 2, 1), 8, "Seven eight nine ten", &[]),
+        ];
+        let stats = Stats::compute(&entries, today());
+        assert_eq!(stats.entries, 4);
+        assert_eq!(stats.words, 10);
+        assert_eq!(stats.average_words(), 2);
+        assert_eq!(stats.words_this_week, 5);
+        assert_eq!(stats.words_last_week, 1);
+        assert_eq!(stats.by_weekday, [1, 0, 0, 2, 0, 0, 1]);
+        assert_eq!(stats.by_hour[8], 2);
+        assert_eq!(stats.by_hour[22], 2);
+        assert_eq!(Stats::compute(&[], today()).average_words(), 0);
+    }
+
+    #[test]
+    fn ranks_tags_with_their_monthly_trend() {
+        let entries = vec![
+            entry(date(2023, 1, 5), 9, "Old", &["work"]),
+            entry(date(2024, 1, 5), 9, "A", &["work", "family"]),
+            entry(date(2024, 3, 5), 9, "B", &["work"]),
+            entry(date(2024, 3, 6), 9, "C", &["family", "work"]),
+            entry(date(2024, 3, 7), 9, "D", &["books"]),
+        ];
+        let stats = Stats::compute(&entries, today());
+        let ranked: Vec<(&str, usize)> = stats
+            .top_tags
+            .iter()
+            .map(|t| (t.tag.as_str(), t.count))
+            .collect();
+        assert_eq!(ranked, [("work", 4), ("family", 2), ("books", 1)]);
+        assert_eq!(stats.top_tags[0].by_month, [0, 0, 0, 1, 0, 2]);
+    }
+}
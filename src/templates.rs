@@ -0,0 +1,145 @@
+//! Entry templates, one file each in `diary_templates/`, named after the
+//! file. A template may start with a front matter block giving the tags a
+//! new entry starts with:
+//!
+//! ```text
+//! ---
+//! tags: work, standup
+//! ---
+//! # Standup, {{weekday}} {{date}}
+//! Yesterday: {{cursor}}
+//! ```
+//!
+//! `{{date}}` and `{{weekday}}` become the entry's day, `{{prompt}}` the
+//! writing prompt it answers, and `{{cursor}}` marks where typing starts.
+//! Only `.md` and `.txt` files are templates, so editor backups and the like
+//! are left alone.
+
+use crate::text_area::Pos;
+use chrono::NaiveDate;
+use color_eyre::Result;
+use std::fs;
+use std::path::PathBuf;
+
+const EXTENSIONS: [&str; 2] = ["md", "txt"];
+
+const CURSOR: &str = "{{cursor}}";
+
+pub struct Template {
+    pub name: String,
+    pub tags: Vec<String>,
+    body: String,
+}
+
+impl Template {
+    /// Reads a template's text, which may have Windows line endings.
+    pub fn parse(name: &str, text: &str) -> Self {
+        let text = text.replace("\r\n", "\n");
+        let mut tags = Vec::new();
+        let mut body = text.as_str();
+        if let Some(rest) = text.strip_prefix("---\n") {
+            if let Some((front_matter, after)) = rest.split_once("\n---\n") {
+                for line in front_matter.lines() {
+                    if let Some(list) = line.strip_prefix("tags:") {
+                        tags.extend(
+                            list.split(',')
+                                .map(|t| t.trim().to_string())
+                                .filter(|t| !t.is_empty()),
+                        );
+                    }
+                }
+                body = after;
+            }
+        }
+        Template {
+            name: name.to_string(),
+            tags,
+            body: body.to_string(),
+        }
+    }
+
+    /// Whether the template has a place for the writing prompt.
+    pub fn asks_prompt(&self) -> bool {
+        self.body.contains("{{prompt}}")
+    }
+
+    /// The template's text for an entry on `day` answering `prompt`, and
+    /// where its cursor placeholder was, if it has one.
+    pub fn fill(&self, day: NaiveDate, prompt: Option<&str>) -> (String, Option<Pos>) {
+        let text = self
+            .body
+            .replace("{{date}}", &day.format("%Y-%m-%d").to_string())
+            .replace("{{weekday}}", &day.format("%A").to_string())
+            .replace("{{prompt}}", prompt.unwrap_or(""));
+        let Some(at) = text.find(CURSOR) else {
+            return (text, None);
+        };
+        let before = &text[..at];
+        let row = before.matches('\n').count();
+        let col = before.len() - before.rfind('\n').map_or(0, |i| i + 1);
+        (text.replacen(CURSOR, "", 1), Some(Pos::new(row, col)))
+    }
+}
+
+/// The templates in `diary_templates/`, by name, and the template files
+/// that couldn't be read, such as ones that aren't UTF-8.
+pub fn load_from_dir() -> Result<(Vec<Template>, Vec<PathBuf>)> {
+    let mut templates = Vec::new();
+    let mut unreadable = Vec::new();
+    for file in fs::read_dir("diary_templates")?.flatten() {
+        let path = file.path();
+        let is_template = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| EXTENSIONS.contains(&e));
+        if !is_template || !path.is_file() {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        match fs::read_to_string(&path) {
+            Ok(text) => templates.push(Template::parse(name, &text)),
+            Err(_) => unreadable.push(path),
+        }
+    }
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok((templates, unreadable))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_tags_from_the_front_matter() {
+        let template = Template::parse("review", "---\ntags: weekly, review,\n---\n# Review\n");
+        assert_eq!(template.tags, ["weekly", "review"]);
+        assert_eq!(template.body, "# Review\n");
+
+        let plain = Template::parse("plain", "---no front matter\n");
+        assert!(plain.tags.is_empty());
+        assert_eq!(plain.body, "---no front matter\n");
+
+        let windows = Template::parse("windows", "---\r\ntags: work\r\n---\r\nNotes\r\n");
+        assert_eq!(windows.tags, ["work"]);
+        assert_eq!(windows.body, "Notes\n");
+    }
+
+    #[test]
+    fn fills_in_the_placeholders() {
+        let template = Template::parse(
+            "standup",
+            "# {{weekday}} {{date}}\n> {{prompt}}\nDone: {{cursor}}\n",
+        );
+        let day = NaiveDate::from_ymd_opt(2024, 3, 14).unwrap();
+        assert_eq!(
+            template.fill(day, Some("What went well?")),
+            (
+                "# Thursday 2024-03-14\n> What went well?\nDone: \n".to_string(),
+                Some(Pos::new(2, 6))
+            )
+        );
+        assert_eq!(template.fill(day, None).0.lines().nth(1), Some("> "));
+    }
+}
@@ -0,0 +1,842 @@
+use crate::keymap::{Command, Movement};
+use crate::markdown::{self, Run};
+use ratatui::{
+    layout::{Position, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph},
+    Frame,
+};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// A position in the text: a line index and a byte offset into that line.
+/// The offset always sits on a grapheme boundary.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Pos {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Pos {
+    pub fn new(row: usize, col: usize) -> Self {
+        Pos { row, col }
+    }
+}
+
+/// One screen row of a soft-wrapped line: bytes `start..end` of line `line`.
+#[derive(Clone, Copy)]
+struct VisualRow {
+    line: usize,
+    start: usize,
+    end: usize,
+    last_of_line: bool,
+}
+
+/// How many kills the kill ring keeps.
+const KILL_RING_SIZE: usize = 30;
+
+/// Text deleted with the kill commands, for yanking back. Shared by all the
+/// text inputs.
+#[derive(Default)]
+pub struct KillRing {
+    /// Oldest first.
+    entries: Vec<String>,
+    /// The entry the next yank inserts; yank-pop moves it to older ones.
+    yank_index: usize,
+    /// Whether the last command was a kill, which the next kill adds to.
+    after_kill: bool,
+}
+
+impl KillRing {
+    fn push(&mut self, text: String) {
+        self.entries.push(text);
+        if self.entries.len() > KILL_RING_SIZE {
+            self.entries.remove(0);
+        }
+        self.yank_index = self.entries.len() - 1;
+    }
+
+    /// Adds `text` to the latest kill, before it if it was killed backwards.
+    fn extend(&mut self, text: String, backward: bool) {
+        match self.entries.last_mut() {
+            Some(latest) if backward => latest.insert_str(0, &text),
+            Some(latest) => latest.push_str(&text),
+            None => self.push(text),
+        }
+        self.yank_index = self.entries.len() - 1;
+    }
+
+    fn current(&self) -> Option<&str> {
+        self.entries.get(self.yank_index).map(String::as_str)
+    }
+
+    fn rotate(&mut self) {
+        self.yank_index = match self.yank_index {
+            0 => self.entries.len().saturating_sub(1),
+            i => i - 1,
+        };
+    }
+}
+
+/// The text and cursor of a [`TextArea`], for undo.
+#[derive(Clone)]
+pub struct Snapshot {
+    lines: Vec<String>,
+    cursor: Pos,
+}
+
+/// Editable text with a cursor that moves by grapheme, soft word wrap and
+/// vertical scrolling. Used for the entry content and, in single-line mode,
+/// for the tags input.
+pub struct TextArea {
+    lines: Vec<String>,
+    cursor: Pos,
+    /// The other end of the selection, which runs from here to the cursor.
+    anchor: Option<Pos>,
+    /// Screen column that vertical movement tries to stay in.
+    desired_x: Option<usize>,
+    single_line: bool,
+    /// Whether movement extends the selection, after Emacs' set-mark.
+    mark: bool,
+    /// Where the text just yanked is, until the next edit or movement.
+    yanked: Option<(Pos, Pos)>,
+    /// A range to highlight instead of the selection, for callers that keep
+    /// their own, such as vim's visual mode.
+    highlight: Option<(Pos, Pos)>,
+    /// Whether the text is highlighted as Markdown.
+    markdown: bool,
+    /// First visual row shown.
+    scroll: usize,
+    /// Wrap width and visible rows as of the last render.
+    width: usize,
+    height: usize,
+}
+
+impl TextArea {
+    /// A multi-line text area with the cursor at the end of `text`.
+    pub fn new(text: &str) -> Self {
+        let lines: Vec<String> = text.split('\n').map(|l| l.to_string()).collect();
+        let cursor = Pos::new(lines.len() - 1, lines[lines.len() - 1].len());
+        TextArea {
+            lines,
+            cursor,
+            anchor: None,
+            desired_x: None,
+            single_line: false,
+            mark: false,
+            yanked: None,
+            highlight: None,
+            markdown: false,
+            scroll: 0,
+            width: 80,
+            height: 1,
+        }
+    }
+
+    /// A text area that never contains a line break; Enter is left to the
+    /// caller.
+    pub fn single_line(text: &str) -> Self {
+        TextArea {
+            single_line: true,
+            ..TextArea::new(&text.replace('\n', " "))
+        }
+    }
+
+    /// A multi-line text area that highlights its text as Markdown.
+    pub fn markdown(text: &str) -> Self {
+        TextArea {
+            markdown: true,
+            ..TextArea::new(text)
+        }
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    pub fn cursor(&self) -> Pos {
+        self.cursor
+    }
+
+    pub fn set_highlight(&mut self, range: Option<(Pos, Pos)>) {
+        self.highlight = range;
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            lines: self.lines.clone(),
+            cursor: self.cursor,
+        }
+    }
+
+    /// Puts back the text and cursor from `snapshot`, clearing the selection.
+    pub fn restore(&mut self, snapshot: Snapshot) {
+        self.lines = snapshot.lines;
+        self.cursor = snapshot.cursor;
+        self.clear_selection();
+        self.desired_x = None;
+    }
+
+    /// The selected range, start first, if anything is selected.
+    pub fn selection(&self) -> Option<(Pos, Pos)> {
+        let anchor = self.anchor?;
+        match anchor.cmp(&self.cursor) {
+            std::cmp::Ordering::Less => Some((anchor, self.cursor)),
+            std::cmp::Ordering::Greater => Some((self.cursor, anchor)),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    /// Applies an editing or movement command. Returns whether the text
+    /// changed.
+    pub fn apply(&mut self, command: Command, kill_ring: &mut KillRing) -> bool {
+        let after_kill = std::mem::take(&mut kill_ring.after_kill);
+        match command {
+            Command::Char(c) => {
+                self.insert_str(c.encode_utf8(&mut [0; 4]));
+                return true;
+            }
+            Command::Confirm if !self.single_line => {
+                self.insert_str("\n");
+                return true;
+            }
+            Command::DeleteBackward => return self.backspace(),
+            Command::DeleteForward => return self.delete_forward(),
+            Command::SelectAll => self.select_all(),
+            Command::SetMark => {
+                self.mark = !self.mark;
+                self.anchor = self.mark.then_some(self.cursor);
+            }
+            Command::Move(movement) => self.apply_movement(movement, self.mark),
+            Command::Select(movement) => self.apply_movement(movement, true),
+            Command::KillToLineEnd => {
+                let line_end = Pos::new(self.cursor.row, self.lines[self.cursor.row].len());
+                // At the end of a line, kill the line break instead.
+                let end = if self.cursor == line_end {
+                    self.grapheme_right(self.cursor)
+                } else {
+                    line_end
+                };
+                return self.kill(self.cursor, end, after_kill, kill_ring);
+            }
+            Command::KillWordBackward => {
+                return self.kill(
+                    self.word_left(self.cursor),
+                    self.cursor,
+                    after_kill,
+                    kill_ring,
+                );
+            }
+            Command::KillWordForward => {
+                return self.kill(
+                    self.cursor,
+                    self.word_right(self.cursor),
+                    after_kill,
+                    kill_ring,
+                );
+            }
+            Command::KillRegion => {
+                let (start, end) = self
+                    .selection()
+                    .unwrap_or((self.word_left(self.cursor), self.cursor));
+                return self.kill(start, end, after_kill, kill_ring);
+            }
+            Command::CopyRegion => {
+                if let Some((start, end)) = self.selection() {
+                    kill_ring.push(self.text_range(start, end));
+                    self.clear_selection();
+                }
+            }
+            Command::Yank => {
+                let Some(text) = kill_ring.current().map(str::to_string) else {
+                    return false;
+                };
+                return self.yank(&text);
+            }
+            Command::YankPop => {
+                let Some((start, end)) = self.yanked else {
+                    return false;
+                };
+                kill_ring.rotate();
+                let text = kill_ring.current().unwrap_or_default().to_string();
+                self.delete_range(start, end);
+                return self.yank(&text);
+            }
+            _ => {}
+        }
+        false
+    }
+
+    fn apply_movement(&mut self, movement: Movement, select: bool) {
+        match movement {
+            Movement::WordLeft => self.move_to(self.word_left(self.cursor), select),
+            Movement::WordRight => self.move_to(self.word_right(self.cursor), select),
+            Movement::Left => match self.selection() {
+                Some((start, _)) if !select => self.move_to(start, false),
+                _ => self.move_to(self.grapheme_left(self.cursor), select),
+            },
+            Movement::Right => match self.selection() {
+                Some((_, end)) if !select => self.move_to(end, false),
+                _ => self.move_to(self.grapheme_right(self.cursor), select),
+            },
+            Movement::Up => self.move_vertically(-1, select),
+            Movement::Down => self.move_vertically(1, select),
+            Movement::PageUp => self.move_vertically(-(self.height as isize), select),
+            Movement::PageDown => self.move_vertically(self.height as isize, select),
+            Movement::Start => self.move_to(Pos::default(), select),
+            Movement::End => self.move_to(self.end(), select),
+            Movement::LineStart => self.move_to(Pos::new(self.cursor.row, 0), select),
+            Movement::LineEnd => self.move_to(
+                Pos::new(self.cursor.row, self.lines[self.cursor.row].len()),
+                select,
+            ),
+        }
+    }
+
+    /// Deletes `start..end` into the kill ring. Consecutive kills collect
+    /// into one kill ring entry.
+    fn kill(&mut self, start: Pos, end: Pos, after_kill: bool, kill_ring: &mut KillRing) -> bool {
+        if start == end {
+            return false;
+        }
+        let backward = end == self.cursor && start != self.cursor;
+        let text = self.delete_range(start, end);
+        if after_kill {
+            kill_ring.extend(text, backward);
+        } else {
+            kill_ring.push(text);
+        }
+        kill_ring.after_kill = true;
+        true
+    }
+
+    fn yank(&mut self, text: &str) -> bool {
+        self.clear_selection();
+        let start = self.cursor;
+        self.insert_str(text);
+        self.yanked = Some((start, self.cursor));
+        true
+    }
+
+    /// Clears the selection. Every edit and plain movement goes through
+    /// here, which also ends the chance to yank-pop.
+    pub fn clear_selection(&mut self) {
+        self.anchor = None;
+        self.mark = false;
+        self.yanked = None;
+    }
+
+    pub fn select_all(&mut self) {
+        self.anchor = Some(Pos::default());
+        self.cursor = self.end();
+    }
+
+    /// Inserts `text` at the cursor, replacing the selection.
+    pub fn insert_str(&mut self, text: &str) {
+        self.delete_selection();
+        let text = if self.single_line {
+            text.replace('\n', " ")
+        } else {
+            text.to_string()
+        };
+
+        let Pos { row, col } = self.cursor;
+        let rest = self.lines[row].split_off(col);
+        let mut parts = text.split('\n');
+        self.lines[row].push_str(parts.next().unwrap_or_default());
+        let mut last_row = row;
+        for part in parts {
+            last_row += 1;
+            self.lines.insert(last_row, part.to_string());
+        }
+        self.cursor = Pos::new(last_row, self.lines[last_row].len());
+        self.lines[last_row].push_str(&rest);
+        self.clear_selection();
+        self.desired_x = None;
+    }
+
+    /// Deletes the selection, returning the deleted text.
+    pub fn delete_selection(&mut self) -> Option<String> {
+        let (start, end) = self.selection()?;
+        Some(self.delete_range(start, end))
+    }
+
+    /// Deletes the text between `start` and `end`, leaving the cursor at
+    /// `start`, and returns it.
+    pub fn delete_range(&mut self, start: Pos, end: Pos) -> String {
+        let removed = self.text_range(start, end);
+        if start.row == end.row {
+            self.lines[start.row].replace_range(start.col..end.col, "");
+        } else {
+            let tail = self.lines[end.row][end.col..].to_string();
+            self.lines.drain(start.row + 1..=end.row);
+            self.lines[start.row].truncate(start.col);
+            self.lines[start.row].push_str(&tail);
+        }
+        self.cursor = start;
+        self.clear_selection();
+        self.desired_x = None;
+        removed
+    }
+
+    pub fn text_range(&self, start: Pos, end: Pos) -> String {
+        if start.row == end.row {
+            return self.lines[start.row][start.col..end.col].to_string();
+        }
+        let mut text = self.lines[start.row][start.col..].to_string();
+        for line in &self.lines[start.row + 1..end.row] {
+            text.push('\n');
+            text.push_str(line);
+        }
+        text.push('\n');
+        text.push_str(&self.lines[end.row][..end.col]);
+        text
+    }
+
+    pub fn backspace(&mut self) -> bool {
+        if self.delete_selection().is_some() {
+            return true;
+        }
+        let start = self.grapheme_left(self.cursor);
+        if start == self.cursor {
+            return false;
+        }
+        self.delete_range(start, self.cursor);
+        true
+    }
+
+    pub fn delete_forward(&mut self) -> bool {
+        if self.delete_selection().is_some() {
+            return true;
+        }
+        let end = self.grapheme_right(self.cursor);
+        if end == self.cursor {
+            return false;
+        }
+        self.delete_range(self.cursor, end);
+        true
+    }
+
+    /// Moves the cursor, extending the selection if `select` is set and
+    /// clearing it otherwise.
+    pub fn move_to(&mut self, pos: Pos, select: bool) {
+        if select {
+            self.anchor.get_or_insert(self.cursor);
+            self.yanked = None;
+        } else {
+            self.clear_selection();
+        }
+        self.cursor = pos;
+        self.desired_x = None;
+    }
+
+    /// The position after the last character.
+    pub fn end(&self) -> Pos {
+        let row = self.lines.len() - 1;
+        Pos::new(row, self.lines[row].len())
+    }
+
+    /// The position one grapheme before `pos`, wrapping to the end of the
+    /// previous line.
+    pub fn grapheme_left(&self, pos: Pos) -> Pos {
+        if pos.col == 0 {
+            return match pos.row {
+                0 => pos,
+                row => Pos::new(row - 1, self.lines[row - 1].len()),
+            };
+        }
+        let col = self.lines[pos.row][..pos.col]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(i, _)| i);
+        Pos::new(pos.row, col)
+    }
+
+    /// The position one grapheme after `pos`, wrapping to the start of the
+    /// next line.
+    pub fn grapheme_right(&self, pos: Pos) -> Pos {
+        let line = &self.lines[pos.row];
+        if pos.col == line.len() {
+            return if pos.row + 1 < self.lines.len() {
+                Pos::new(pos.row + 1, 0)
+            } else {
+                pos
+            };
+        }
+        let len = line[pos.col..].graphemes(true).next().map_or(0, str::len);
+        Pos::new(pos.row, pos.col + len)
+    }
+
+    /// The start of the word before `pos`.
+    pub fn word_left(&self, pos: Pos) -> Pos {
+        if pos.col == 0 {
+            return self.grapheme_left(pos);
+        }
+        let line = &self.lines[pos.row];
+        let mut graphemes = line[..pos.col].grapheme_indices(true).rev().peekable();
+        while graphemes.next_if(|(_, g)| !is_word(g)).is_some() {}
+        let mut col = pos.col;
+        while let Some((i, _)) = graphemes.next_if(|(_, g)| is_word(g)) {
+            col = i;
+        }
+        if col == pos.col {
+            col = 0;
+        }
+        Pos::new(pos.row, col)
+    }
+
+    /// The end of the word after `pos`.
+    pub fn word_right(&self, pos: Pos) -> Pos {
+        let line = &self.lines[pos.row];
+        if pos.col == line.len() {
+            return self.grapheme_right(pos);
+        }
+        let mut graphemes = line[pos.col..].grapheme_indices(true).peekable();
+        while graphemes.next_if(|(_, g)| !is_word(g)).is_some() {}
+        while graphemes.next_if(|(_, g)| is_word(g)).is_some() {}
+        let col = graphemes.peek().map_or(line.len(), |(i, _)| pos.col + i);
+        Pos::new(pos.row, col)
+    }
+
+    /// The screen column of `pos` within its line, ignoring wrapping.
+    pub fn line_x(&self, pos: Pos) -> usize {
+        self.lines[pos.row][..pos.col].width()
+    }
+
+    /// The position in line `row` closest to screen column `x`, ignoring
+    /// wrapping.
+    pub fn line_pos_at_x(&self, row: usize, x: usize) -> Pos {
+        self.pos_at_x(
+            VisualRow {
+                line: row,
+                start: 0,
+                end: self.lines[row].len(),
+                last_of_line: true,
+            },
+            x,
+        )
+    }
+
+    /// Moves the cursor by `rows` screen rows, keeping to the same screen
+    /// column where the target row is long enough.
+    fn move_vertically(&mut self, rows: isize, select: bool) {
+        let visual_rows = self.visual_rows();
+        let current = self.visual_row_of(&visual_rows, self.cursor);
+        let x = self.desired_x.unwrap_or_else(|| {
+            let row = visual_rows[current];
+            self.lines[row.line][row.start..self.cursor.col].width()
+        });
+
+        let target = (current as isize + rows).clamp(0, visual_rows.len() as isize - 1) as usize;
+        let pos = if target == current && rows < 0 {
+            Pos::new(self.cursor.row, visual_rows[current].start)
+        } else if target == current && rows > 0 {
+            let row = visual_rows[current];
+            Pos::new(row.line, row.end)
+        } else {
+            self.pos_at_x(visual_rows[target], x)
+        };
+
+        self.move_to(pos, select);
+        self.desired_x = Some(x);
+    }
+
+    /// The position in `row` closest to screen column `x`.
+    fn pos_at_x(&self, row: VisualRow, x: usize) -> Pos {
+        let line = &self.lines[row.line];
+        let mut width = 0;
+        let mut last_start = row.start;
+        for (i, g) in line[row.start..row.end].grapheme_indices(true) {
+            let g_width = g.width();
+            if width + g_width > x {
+                return Pos::new(row.line, row.start + i);
+            }
+            width += g_width;
+            last_start = row.start + i;
+        }
+        // Past the end of the row. Unless this is the end of the line, the
+        // end of the row is the start of the next one, so stop before it.
+        if row.last_of_line {
+            Pos::new(row.line, row.end)
+        } else {
+            Pos::new(row.line, last_start)
+        }
+    }
+
+    fn visual_rows(&self) -> Vec<VisualRow> {
+        let mut rows = Vec::new();
+        for (index, line) in self.lines.iter().enumerate() {
+            let wrapped = wrap_line(line, self.width);
+            let count = wrapped.len();
+            rows.extend(
+                wrapped
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, (start, end))| VisualRow {
+                        line: index,
+                        start,
+                        end,
+                        last_of_line: i + 1 == count,
+                    }),
+            );
+        }
+        rows
+    }
+
+    fn visual_row_of(&self, rows: &[VisualRow], pos: Pos) -> usize {
+        rows.iter()
+            .position(|r| {
+                r.line == pos.row && pos.col >= r.start && (pos.col < r.end || r.last_of_line)
+            })
+            .unwrap_or(0)
+    }
+
+    /// Draws the text inside `block`, scrolled so the cursor is visible, and
+    /// places the terminal cursor if `focused`.
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, block: Block, focused: bool) {
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        // Keep one column free so the cursor fits after a full row.
+        self.width = (inner.width as usize).saturating_sub(1).max(1);
+        self.height = (inner.height as usize).max(1);
+
+        let rows = self.visual_rows();
+        let cursor_row = self.visual_row_of(&rows, self.cursor);
+        self.scroll = self.scroll.min(rows.len().saturating_sub(self.height));
+        if cursor_row < self.scroll {
+            self.scroll = cursor_row;
+        } else if cursor_row >= self.scroll + self.height {
+            self.scroll = cursor_row + 1 - self.height;
+        }
+
+        let selection = self.highlight.or(self.selection());
+        let runs = if self.markdown {
+            markdown::highlight_source(&self.lines)
+        } else {
+            Vec::new()
+        };
+        let lines: Vec<Line> = rows
+            .iter()
+            .skip(self.scroll)
+            .take(self.height)
+            .map(|row| {
+                let row_runs = runs.get(row.line).map_or(&[][..], |r| r);
+                self.render_row(*row, row_runs, selection)
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines), inner);
+
+        if focused {
+            let row = rows[cursor_row];
+            let x = self.lines[row.line][row.start..self.cursor.col].width();
+            frame.set_cursor_position(Position::new(
+                inner.x + x as u16,
+                inner.y + (cursor_row - self.scroll) as u16,
+            ));
+        }
+    }
+
+    fn render_row(
+        &self,
+        row: VisualRow,
+        runs: &[Run],
+        selection: Option<(Pos, Pos)>,
+    ) -> Line<'static> {
+        let text = &self.lines[row.line][row.start..row.end];
+        if runs.is_empty() && selection.is_none() {
+            return Line::raw(text.to_string());
+        }
+
+        // Clamp the selection to this row, in bytes relative to the row.
+        let clamp = |pos: Pos| match pos.row.cmp(&row.line) {
+            std::cmp::Ordering::Less => 0,
+            std::cmp::Ordering::Greater => text.len(),
+            std::cmp::Ordering::Equal => pos.col.clamp(row.start, row.end) - row.start,
+        };
+        let selected = selection.map(|(start, end)| clamp(start)..clamp(end));
+
+        // Split the row wherever a style starts or ends.
+        let mut cuts = vec![0, text.len()];
+        for (range, _) in runs {
+            for cut in [range.start, range.end] {
+                if cut > row.start && cut < row.end {
+                    cuts.push(cut - row.start);
+                }
+            }
+        }
+        if let Some(selected) = &selected {
+            cuts.extend([selected.start, selected.end]);
+        }
+        cuts.sort_unstable();
+        cuts.dedup();
+
+        let spans: Vec<Span> = cuts
+            .windows(2)
+            .map(|cut| {
+                let (from, to) = (cut[0], cut[1]);
+                let mut style = runs
+                    .iter()
+                    .filter(|(range, _)| {
+                        range.start <= row.start + from && row.start + to <= range.end
+                    })
+                    .fold(Style::default(), |style, (_, run)| style.patch(*run));
+                if selected
+                    .as_ref()
+                    .is_some_and(|s| s.start <= from && to <= s.end)
+                {
+                    style = style.add_modifier(Modifier::REVERSED);
+                }
+                Span::styled(text[from..to].to_string(), style)
+            })
+            .collect();
+        Line::from(spans)
+    }
+}
+
+fn is_word(grapheme: &str) -> bool {
+    grapheme
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Splits `line` into rows at most `width` columns wide, breaking after
+/// whitespace where possible. Returns byte ranges; an empty line is one empty
+/// row.
+fn wrap_line(line: &str, width: usize) -> Vec<(usize, usize)> {
+    let mut rows = Vec::new();
+    let mut start = 0;
+    let mut row_width = 0;
+    let mut break_at = None;
+
+    for (i, g) in line.grapheme_indices(true) {
+        let g_width = g.width();
+        if row_width + g_width > width && i > start {
+            let end = match break_at {
+                Some(b) if b > start => b,
+                _ => i,
+            };
+            rows.push((start, end));
+            start = end;
+            row_width = line[start..i].width();
+            break_at = None;
+
+            if row_width + g_width > width && i > start {
+                rows.push((start, i));
+                start = i;
+                row_width = 0;
+            }
+        }
+        row_width += g_width;
+        if g.chars().all(char::is_whitespace) {
+            break_at = Some(i + g.len());
+        }
+    }
+    rows.push((start, line.len()));
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(area: &mut TextArea, commands: &[Command]) {
+        let mut kill_ring = KillRing::default();
+        for &command in commands {
+            area.apply(command, &mut kill_ring);
+        }
+    }
+
+    #[test]
+    fn moves_and_deletes_whole_graphemes() {
+        // An e with a combining acute accent, a family emoji joined from four
+        // people, and a flag made of two regional indicators.
+        let family = "👨\u{200d}👩\u{200d}👧\u{200d}👦";
+        let mut area = TextArea::new("");
+        area.insert_str(&format!("ce\u{301}{}🇳🇿!", family));
+        let end = area.cursor();
+
+        apply(&mut area, &[Command::Move(Movement::Left)]);
+        assert_eq!(area.cursor(), Pos::new(0, end.col - 1));
+        apply(&mut area, &[Command::Move(Movement::Left)]);
+        assert_eq!(area.cursor(), Pos::new(0, end.col - 1 - "🇳🇿".len()));
+        apply(&mut area, &[Command::DeleteBackward]);
+        assert_eq!(area.text(), "ce\u{301}🇳🇿!");
+        apply(&mut area, &[Command::DeleteBackward]);
+        assert_eq!(area.text(), "c🇳🇿!");
+        apply(&mut area, &[Command::DeleteForward]);
+        assert_eq!(area.text(), "c!");
+        assert_eq!(area.cursor(), Pos::new(0, 1));
+    }
+
+    #[test]
+    fn moves_by_words_across_cjk_and_accents() {
+        let mut area = TextArea::new("naïve 日本語 test");
+        area.move_to(Pos::default(), false);
+        apply(&mut area, &[Command::Move(Movement::WordRight)]);
+        assert_eq!(area.cursor(), Pos::new(0, "naïve".len()));
+        apply(&mut area, &[Command::Move(Movement::WordRight)]);
+        assert_eq!(area.cursor(), Pos::new(0, "naïve 日本語".len()));
+        apply(&mut area, &[Command::KillWordBackward]);
+        assert_eq!(area.text(), "naïve  test");
+    }
+
+    #[test]
+    fn wraps_wide_characters_by_their_width() {
+        // Each of these takes two columns, so three fit in seven.
+        assert_eq!(
+            wrap_line("日本語の文章", 7),
+            [(0, 9), (9, 18)],
+            "a wide character never straddles the edge"
+        );
+        assert_eq!(wrap_line("ab cd", 3), [(0, 3), (3, 5)]);
+        assert_eq!(wrap_line("", 3), [(0, 0)]);
+        // A character wider than the row still gets a row of its own.
+        assert_eq!(wrap_line("日本", 1), [(0, 3), (3, 6)]);
+    }
+
+    #[test]
+    fn moves_vertically_by_screen_column() {
+        let mut area = TextArea::new("日本語\nabcdef");
+        area.move_to(Pos::new(1, 4), false);
+        apply(&mut area, &[Command::Move(Movement::Up)]);
+        // Column four is the start of the third wide character.
+        assert_eq!(area.cursor(), Pos::new(0, "日本".len()));
+        assert_eq!(area.line_x(area.cursor()), 4);
+
+        // Column three is the middle of one, so the cursor stops before it.
+        area.move_to(Pos::new(1, 3), false);
+        apply(&mut area, &[Command::Move(Movement::Up)]);
+        assert_eq!(area.cursor(), Pos::new(0, "日".len()));
+
+        // Within a wrapped line, the rows are the wrapped ones.
+        let mut area = TextArea::new("日本語の文章");
+        area.width = 7;
+        area.move_to(Pos::new(0, "日本語の".len()), false);
+        apply(&mut area, &[Command::Move(Movement::Up)]);
+        assert_eq!(area.cursor(), Pos::new(0, "日".len()));
+        apply(&mut area, &[Command::Move(Movement::Down)]);
+        assert_eq!(area.cursor(), Pos::new(0, "日本語の".len()));
+    }
+
+    #[test]
+    fn selects_and_replaces_text() {
+        let mut area = TextArea::new("héllo wörld");
+        apply(
+            &mut area,
+            &[Command::Select(Movement::WordLeft), Command::Char('ü')],
+        );
+        assert_eq!(area.text(), "héllo ü");
+        assert_eq!(area.selection(), None);
+
+        let mut line = TextArea::single_line("one\ntwo");
+        apply(&mut line, &[Command::Confirm]);
+        assert_eq!(line.text(), "one two");
+    }
+}
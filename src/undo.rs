@@ -0,0 +1,118 @@
+/// How many steps are kept before the oldest are forgotten.
+const MAX_STEPS: usize = 500;
+
+/// Undo and redo stacks of editor states `S`.
+///
+/// Each change is recorded with the state from before it. Consecutive changes
+/// recorded with the same run key `K`, such as typing one word, are coalesced
+/// into a single step.
+pub struct History<S, K> {
+    undo: Vec<S>,
+    redo: Vec<S>,
+    run: Option<K>,
+}
+
+impl<S, K> Default for History<S, K> {
+    fn default() -> Self {
+        History {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            run: None,
+        }
+    }
+}
+
+impl<S, K: PartialEq> History<S, K> {
+    /// Records a change made to `before`. A change with no run key is always
+    /// its own step.
+    pub fn record(&mut self, before: S, run: Option<K>) {
+        if run.is_none() || run != self.run {
+            self.undo.push(before);
+            if self.undo.len() > MAX_STEPS {
+                self.undo.remove(0);
+            }
+        }
+        self.redo.clear();
+        self.run = run;
+    }
+
+    /// Makes the next change start a new step, e.g. after the cursor moved.
+    pub fn break_run(&mut self) {
+        self.run = None;
+    }
+
+    /// The state to go back to, given the `current` one.
+    pub fn undo(&mut self, current: S) -> Option<S> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current);
+        self.run = None;
+        Some(previous)
+    }
+
+    /// The state to go forward to again, given the `current` one.
+    pub fn redo(&mut self, current: S) -> Option<S> {
+        let next = self.redo.pop()?;
+        self.undo.push(current);
+        self.run = None;
+        Some(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coalesces_runs_into_one_step() {
+        let mut history: History<&str, char> = History::default();
+        history.record("", Some('w'));
+        history.record("h", Some('w'));
+        history.record("hi", Some('w'));
+        history.record("hi ", None);
+        history.record("hi!", Some('w'));
+        assert_eq!(history.undo("hi!x"), Some("hi!"));
+        assert_eq!(history.undo("hi!"), Some("hi "));
+        assert_eq!(history.undo("hi "), Some(""));
+        assert_eq!(history.undo(""), None);
+    }
+
+    #[test]
+    fn breaking_a_run_starts_a_new_step() {
+        let mut history: History<&str, char> = History::default();
+        history.record("", Some('w'));
+        history.break_run();
+        history.record("a", Some('w'));
+        assert_eq!(history.undo("ab"), Some("a"));
+        // After an undo, typing on starts a new step too.
+        history.record("a", Some('w'));
+        assert_eq!(history.undo("ac"), Some("a"));
+        assert_eq!(history.undo("a"), Some(""));
+    }
+
+    #[test]
+    fn a_new_change_discards_the_redo_steps() {
+        let mut history: History<&str, char> = History::default();
+        history.record("", None);
+        history.record("a", None);
+        assert_eq!(history.undo("ab"), Some("a"));
+        assert_eq!(history.undo("a"), Some(""));
+        assert_eq!(history.redo(""), Some("a"));
+        history.record("a", None);
+        assert_eq!(history.redo("ax"), None);
+        assert_eq!(history.undo("ax"), Some("a"));
+        assert_eq!(history.undo("a"), Some(""));
+    }
+
+    #[test]
+    fn forgets_the_oldest_steps() {
+        let mut history: History<usize, ()> = History::default();
+        for step in 0..MAX_STEPS + 10 {
+            history.record(step, None);
+        }
+        let mut oldest = None;
+        while let Some(step) = history.undo(0) {
+            oldest = Some(step);
+        }
+        assert_eq!(oldest, Some(10));
+    }
+}
@@ -0,0 +1,1002 @@
+use crate::text_area::{Pos, TextArea};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::str::Chars;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VimMode {
+    Normal,
+    Insert,
+    Visual,
+    VisualLine,
+}
+
+impl VimMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            VimMode::Normal => "NORMAL",
+            VimMode::Insert => "INSERT",
+            VimMode::Visual => "VISUAL",
+            VimMode::VisualLine => "VISUAL LINE",
+        }
+    }
+}
+
+/// What the editor should do after [`Vim::input`].
+#[derive(Debug, PartialEq, Eq)]
+pub enum VimOutcome {
+    /// A key typed in insert mode, for the text area to handle as usual.
+    Insert,
+    Unchanged,
+    Changed,
+    Undo,
+    Redo,
+    /// Save the entry and close the editor: `ZZ`, `:wq` or `:x`.
+    Save,
+    /// `:w`, which saves a draft since the entry is saved on closing.
+    SaveDraft,
+    /// Close the editor unless there are unsaved changes: `:q`.
+    Quit,
+    /// Close the editor, discarding changes: `:q!` or `ZQ`.
+    Discard,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Motion {
+    Left,
+    Right,
+    Up,
+    Down,
+    WordForward,
+    WordBackward,
+    WordEnd,
+    LineStart,
+    LineEnd,
+    FirstLine,
+    LastLine,
+}
+
+/// Which part of the text between the cursor and a motion's target the motion
+/// covers when used with an operator.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum MotionKind {
+    /// Up to but not including the target.
+    Exclusive,
+    /// Up to and including the grapheme at the target.
+    Inclusive,
+    /// Whole lines.
+    Linewise,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Delete,
+    Change,
+    Yank,
+}
+
+enum Command {
+    Move(Motion),
+    /// An operator with a motion, or with `None` when doubled as in `dd`.
+    Operate(Operator, Option<Motion>),
+    /// Any other single-key command, or an operator in visual mode.
+    Key(char),
+    /// `ZZ`.
+    Save,
+    /// `ZQ`.
+    Discard,
+}
+
+enum Parsed {
+    Incomplete,
+    Invalid,
+    Complete {
+        register: Option<char>,
+        count: Option<usize>,
+        command: Command,
+    },
+}
+
+#[derive(Clone)]
+struct Register {
+    text: String,
+    linewise: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Blank,
+    EmptyLine,
+    Word,
+    Punctuation,
+}
+
+/// Vim-style modal editing on top of a [`TextArea`]. Insert mode uses the
+/// text area's own keys; normal and visual mode commands are collected in
+/// `pending` until they parse as a complete command.
+pub struct Vim {
+    mode: VimMode,
+    pending: String,
+    /// The `:` command being typed, if any.
+    command_line: Option<String>,
+    /// An error to show in the status line until the next key.
+    message: Option<String>,
+    registers: HashMap<char, Register>,
+    visual_anchor: Pos,
+    /// Screen column that `j` and `k` try to stay in; `usize::MAX` after `$`.
+    column: Option<usize>,
+}
+
+impl Vim {
+    pub fn new() -> Self {
+        Vim {
+            mode: VimMode::Normal,
+            pending: String::new(),
+            command_line: None,
+            message: None,
+            registers: HashMap::new(),
+            visual_anchor: Pos::default(),
+            column: None,
+        }
+    }
+
+    pub fn mode(&self) -> VimMode {
+        self.mode
+    }
+
+    /// The typed-ahead part of a command, the `:` command line or an error,
+    /// for the status line.
+    pub fn status(&self) -> String {
+        if let Some(command) = &self.command_line {
+            format!(":{}", command)
+        } else if let Some(message) = &self.message {
+            message.clone()
+        } else {
+            self.pending.clone()
+        }
+    }
+
+    /// Puts the cursor back on a grapheme after the text was replaced, e.g.
+    /// by undo.
+    pub fn normalize(&mut self, area: &mut TextArea) {
+        self.set_mode(area, VimMode::Normal);
+        self.pending.clear();
+        clamp_cursor(area);
+    }
+
+    pub fn input(&mut self, area: &mut TextArea, key: KeyEvent) -> VimOutcome {
+        self.message = None;
+        if self.command_line.is_some() {
+            return self.command_line_input(key);
+        }
+        if self.mode == VimMode::Insert {
+            if key.code == KeyCode::Esc {
+                let cursor = area.cursor();
+                area.move_to(left_in_line(area, cursor), false);
+                self.set_mode(area, VimMode::Normal);
+                return VimOutcome::Unchanged;
+            }
+            return VimOutcome::Insert;
+        }
+
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let c = match key.code {
+            KeyCode::Esc => {
+                self.pending.clear();
+                if self.mode != VimMode::Normal {
+                    self.set_mode(area, VimMode::Normal);
+                }
+                return VimOutcome::Unchanged;
+            }
+            KeyCode::Char('r') if ctrl && self.pending.is_empty() => return VimOutcome::Redo,
+            KeyCode::Char(c) if !ctrl => c,
+            KeyCode::Left | KeyCode::Backspace => 'h',
+            KeyCode::Right => 'l',
+            KeyCode::Up => 'k',
+            KeyCode::Down | KeyCode::Enter => 'j',
+            KeyCode::Home => '0',
+            KeyCode::End => '$',
+            _ => return VimOutcome::Unchanged,
+        };
+        self.pending.push(c);
+
+        let visual = self.mode != VimMode::Normal;
+        let (register, count, command) = match parse(&self.pending, visual) {
+            Parsed::Incomplete => return VimOutcome::Unchanged,
+            Parsed::Invalid => {
+                self.pending.clear();
+                return VimOutcome::Unchanged;
+            }
+            Parsed::Complete {
+                register,
+                count,
+                command,
+            } => (register, count, command),
+        };
+        self.pending.clear();
+
+        let outcome = if visual {
+            self.visual_command(area, register, count, command)
+        } else {
+            self.normal_command(area, register, count, command)
+        };
+        if self.mode == VimMode::Normal {
+            clamp_cursor(area);
+        }
+        self.update_highlight(area);
+        outcome
+    }
+
+    fn command_line_input(&mut self, key: KeyEvent) -> VimOutcome {
+        let Some(command) = &mut self.command_line else {
+            return VimOutcome::Unchanged;
+        };
+        match key.code {
+            KeyCode::Esc => self.command_line = None,
+            KeyCode::Backspace if command.is_empty() => self.command_line = None,
+            KeyCode::Backspace => {
+                command.pop();
+            }
+            KeyCode::Char(c) => command.push(c),
+            KeyCode::Enter => {
+                let command = self.command_line.take().unwrap_or_default();
+                match command.trim() {
+                    "wq" | "x" => return VimOutcome::Save,
+                    "w" => return VimOutcome::SaveDraft,
+                    "q" => return VimOutcome::Quit,
+                    "q!" => return VimOutcome::Discard,
+                    "" => {}
+                    other => self.message = Some(format!("Not an editor command: {}", other)),
+                }
+            }
+            _ => {}
+        }
+        VimOutcome::Unchanged
+    }
+
+    fn normal_command(
+        &mut self,
+        area: &mut TextArea,
+        register: Option<char>,
+        count: Option<usize>,
+        command: Command,
+    ) -> VimOutcome {
+        let times = count.unwrap_or(1);
+        let cursor = area.cursor();
+        let line_len = area.lines()[cursor.row].len();
+
+        match command {
+            Command::Move(motion) => {
+                let (target, _) = self.motion_target(area, motion, count);
+                area.move_to(target, false);
+                VimOutcome::Unchanged
+            }
+            Command::Operate(operator, motion) => {
+                self.operate(area, register, count, operator, motion)
+            }
+            Command::Save => VimOutcome::Save,
+            Command::Discard => VimOutcome::Discard,
+            Command::Key(c) => match c {
+                'i' => {
+                    self.set_mode(area, VimMode::Insert);
+                    VimOutcome::Unchanged
+                }
+                'a' => {
+                    area.move_to(right_in_line(area, cursor), false);
+                    self.set_mode(area, VimMode::Insert);
+                    VimOutcome::Unchanged
+                }
+                'I' => {
+                    area.move_to(first_non_blank(area, cursor.row), false);
+                    self.set_mode(area, VimMode::Insert);
+                    VimOutcome::Unchanged
+                }
+                'A' => {
+                    area.move_to(Pos::new(cursor.row, line_len), false);
+                    self.set_mode(area, VimMode::Insert);
+                    VimOutcome::Unchanged
+                }
+                'o' => {
+                    area.move_to(Pos::new(cursor.row, line_len), false);
+                    area.insert_str("\n");
+                    self.set_mode(area, VimMode::Insert);
+                    VimOutcome::Changed
+                }
+                'O' => {
+                    area.move_to(Pos::new(cursor.row, 0), false);
+                    area.insert_str("\n");
+                    area.move_to(Pos::new(cursor.row, 0), false);
+                    self.set_mode(area, VimMode::Insert);
+                    VimOutcome::Changed
+                }
+                'v' | 'V' => {
+                    self.visual_anchor = cursor;
+                    let mode = if c == 'v' {
+                        VimMode::Visual
+                    } else {
+                        VimMode::VisualLine
+                    };
+                    self.set_mode(area, mode);
+                    VimOutcome::Unchanged
+                }
+                'x' | 'X' => {
+                    let mut start = cursor;
+                    let mut end = cursor;
+                    for _ in 0..times {
+                        if c == 'x' {
+                            end = right_in_line(area, end);
+                        } else {
+                            start = left_in_line(area, start);
+                        }
+                    }
+                    if start == end {
+                        return VimOutcome::Unchanged;
+                    }
+                    let text = area.delete_range(start, end);
+                    self.set_register(register, text, false);
+                    VimOutcome::Changed
+                }
+                'D' => self.operate(
+                    area,
+                    register,
+                    count,
+                    Operator::Delete,
+                    Some(Motion::LineEnd),
+                ),
+                'C' => self.operate(
+                    area,
+                    register,
+                    count,
+                    Operator::Change,
+                    Some(Motion::LineEnd),
+                ),
+                'Y' => self.operate(area, register, count, Operator::Yank, None),
+                'p' | 'P' => self.paste(area, register, times, c == 'p'),
+                'u' => VimOutcome::Undo,
+                ':' => {
+                    self.command_line = Some(String::new());
+                    VimOutcome::Unchanged
+                }
+                _ => VimOutcome::Unchanged,
+            },
+        }
+    }
+
+    fn visual_command(
+        &mut self,
+        area: &mut TextArea,
+        register: Option<char>,
+        count: Option<usize>,
+        command: Command,
+    ) -> VimOutcome {
+        match command {
+            Command::Move(motion) => {
+                let (target, _) = self.motion_target(area, motion, count);
+                area.move_to(target, false);
+                VimOutcome::Unchanged
+            }
+            Command::Key('o') => {
+                let cursor = area.cursor();
+                area.move_to(self.visual_anchor, false);
+                self.visual_anchor = cursor;
+                VimOutcome::Unchanged
+            }
+            Command::Key(c @ ('v' | 'V')) => {
+                let mode = if c == 'v' {
+                    VimMode::Visual
+                } else {
+                    VimMode::VisualLine
+                };
+                let mode = if mode == self.mode {
+                    VimMode::Normal
+                } else {
+                    mode
+                };
+                self.set_mode(area, mode);
+                VimOutcome::Unchanged
+            }
+            Command::Key(c @ ('d' | 'x' | 'c' | 's' | 'y')) => {
+                let operator = match c {
+                    'd' | 'x' => Operator::Delete,
+                    'c' | 's' => Operator::Change,
+                    _ => Operator::Yank,
+                };
+                let (start, end, linewise) = self.visual_range(area);
+                self.set_mode(area, VimMode::Normal);
+                self.apply(area, register, operator, start, end, linewise)
+            }
+            _ => VimOutcome::Unchanged,
+        }
+    }
+
+    /// Runs `operator` over the text a motion covers, or over `count` whole
+    /// lines when `motion` is `None`.
+    fn operate(
+        &mut self,
+        area: &mut TextArea,
+        register: Option<char>,
+        count: Option<usize>,
+        operator: Operator,
+        motion: Option<Motion>,
+    ) -> VimOutcome {
+        let cursor = area.cursor();
+        let Some(motion) = motion else {
+            let last = (cursor.row + count.unwrap_or(1) - 1).min(area.lines().len() - 1);
+            return self.apply(
+                area,
+                register,
+                operator,
+                Pos::new(cursor.row, 0),
+                Pos::new(last, 0),
+                true,
+            );
+        };
+
+        // `cw` on a word changes just the word, like `ce`.
+        let motion = match (operator, motion, class_at(area, cursor)) {
+            (Operator::Change, Motion::WordForward, CharClass::Word | CharClass::Punctuation) => {
+                Motion::WordEnd
+            }
+            _ => motion,
+        };
+        let (target, kind) = self.motion_target(area, motion, count);
+        let (start, mut end) = (cursor.min(target), cursor.max(target));
+        match kind {
+            MotionKind::Linewise => return self.apply(area, register, operator, start, end, true),
+            MotionKind::Inclusive => end = right_in_line(area, end),
+            // A word motion that ends at the start of a later line stops at
+            // the end of the previous one instead, so `dw` keeps the break.
+            MotionKind::Exclusive if end.row > start.row && end.col == 0 => {
+                end = Pos::new(end.row - 1, area.lines()[end.row - 1].len());
+            }
+            MotionKind::Exclusive => {}
+        }
+        if start == end && operator != Operator::Change {
+            return VimOutcome::Unchanged;
+        }
+        self.apply(area, register, operator, start, end, false)
+    }
+
+    /// Applies `operator` to `start..end`, or to the lines from `start` to
+    /// `end` if `linewise`.
+    fn apply(
+        &mut self,
+        area: &mut TextArea,
+        register: Option<char>,
+        operator: Operator,
+        start: Pos,
+        end: Pos,
+        linewise: bool,
+    ) -> VimOutcome {
+        let (start, end) = if linewise {
+            (
+                Pos::new(start.row, 0),
+                Pos::new(end.row, area.lines()[end.row].len()),
+            )
+        } else {
+            (start, end)
+        };
+        self.set_register(register, area.text_range(start, end), linewise);
+
+        match operator {
+            Operator::Yank => {
+                area.move_to(start, false);
+                VimOutcome::Unchanged
+            }
+            Operator::Change => {
+                area.delete_range(start, end);
+                self.set_mode(area, VimMode::Insert);
+                VimOutcome::Changed
+            }
+            Operator::Delete if linewise => {
+                let last_row = area.lines().len() - 1;
+                if end.row < last_row {
+                    area.delete_range(start, Pos::new(end.row + 1, 0));
+                } else if start.row > 0 {
+                    let previous = start.row - 1;
+                    area.delete_range(Pos::new(previous, area.lines()[previous].len()), end);
+                    area.move_to(Pos::new(previous, 0), false);
+                } else {
+                    area.delete_range(start, end);
+                }
+                let row = area.cursor().row;
+                area.move_to(first_non_blank(area, row), false);
+                VimOutcome::Changed
+            }
+            Operator::Delete => {
+                area.delete_range(start, end);
+                VimOutcome::Changed
+            }
+        }
+    }
+
+    fn paste(
+        &mut self,
+        area: &mut TextArea,
+        register: Option<char>,
+        times: usize,
+        after: bool,
+    ) -> VimOutcome {
+        let name = register.map_or('"', |r| r.to_ascii_lowercase());
+        let Some(Register { text, linewise }) = self.registers.get(&name).cloned() else {
+            self.message = Some(format!("Nothing in register {}", name));
+            return VimOutcome::Unchanged;
+        };
+        let cursor = area.cursor();
+
+        if linewise {
+            let block = vec![text; times].join("\n");
+            if after {
+                let len = area.lines()[cursor.row].len();
+                area.move_to(Pos::new(cursor.row, len), false);
+                area.insert_str(&format!("\n{}", block));
+                area.move_to(Pos::new(cursor.row + 1, 0), false);
+            } else {
+                area.move_to(Pos::new(cursor.row, 0), false);
+                area.insert_str(&format!("{}\n", block));
+                area.move_to(Pos::new(cursor.row, 0), false);
+            }
+        } else {
+            if after {
+                area.move_to(right_in_line(area, cursor), false);
+            }
+            area.insert_str(&text.repeat(times));
+            let end = area.cursor();
+            area.move_to(area.grapheme_left(end), false);
+        }
+        VimOutcome::Changed
+    }
+
+    /// Where `motion` repeated `count` times moves the cursor, and what it
+    /// covers when used with an operator.
+    fn motion_target(
+        &mut self,
+        area: &TextArea,
+        motion: Motion,
+        count: Option<usize>,
+    ) -> (Pos, MotionKind) {
+        let times = count.unwrap_or(1);
+        let cursor = area.cursor();
+        let last_row = area.lines().len() - 1;
+        let repeat = |step: &dyn Fn(Pos) -> Pos| (0..times).fold(cursor, |pos, _| step(pos));
+
+        if !matches!(motion, Motion::Up | Motion::Down) {
+            self.column = None;
+        }
+        match motion {
+            Motion::Left => (repeat(&|p| left_in_line(area, p)), MotionKind::Exclusive),
+            Motion::Right => (repeat(&|p| right_in_line(area, p)), MotionKind::Exclusive),
+            Motion::Up | Motion::Down => {
+                let row = if motion == Motion::Up {
+                    cursor.row.saturating_sub(times)
+                } else {
+                    (cursor.row + times).min(last_row)
+                };
+                let x = *self.column.get_or_insert_with(|| area.line_x(cursor));
+                (area.line_pos_at_x(row, x), MotionKind::Linewise)
+            }
+            Motion::WordForward => (repeat(&|p| next_word_start(area, p)), MotionKind::Exclusive),
+            Motion::WordBackward => (
+                repeat(&|p| previous_word_start(area, p)),
+                MotionKind::Exclusive,
+            ),
+            Motion::WordEnd => (repeat(&|p| word_end(area, p)), MotionKind::Inclusive),
+            Motion::LineStart => (Pos::new(cursor.row, 0), MotionKind::Exclusive),
+            Motion::LineEnd => {
+                self.column = Some(usize::MAX);
+                let row = (cursor.row + times - 1).min(last_row);
+                (
+                    Pos::new(row, area.lines()[row].len()),
+                    MotionKind::Exclusive,
+                )
+            }
+            Motion::FirstLine | Motion::LastLine => {
+                let row = match (motion, count) {
+                    (_, Some(line)) => line.clamp(1, last_row + 1) - 1,
+                    (Motion::FirstLine, None) => 0,
+                    _ => last_row,
+                };
+                (first_non_blank(area, row), MotionKind::Linewise)
+            }
+        }
+    }
+
+    /// The visual selection as a range to operate on, and whether it is
+    /// linewise.
+    fn visual_range(&self, area: &TextArea) -> (Pos, Pos, bool) {
+        let cursor = area.cursor();
+        let (start, end) = (
+            cursor.min(self.visual_anchor),
+            cursor.max(self.visual_anchor),
+        );
+        if self.mode == VimMode::VisualLine {
+            return (start, end, true);
+        }
+        // The selection includes the grapheme under the cursor, or the line
+        // break when that is where the cursor is.
+        let line_len = area.lines()[end.row].len();
+        let end = if end.col < line_len {
+            right_in_line(area, end)
+        } else if end.row + 1 < area.lines().len() {
+            Pos::new(end.row + 1, 0)
+        } else {
+            end
+        };
+        (start, end, false)
+    }
+
+    fn update_highlight(&self, area: &mut TextArea) {
+        let highlight = match self.mode {
+            VimMode::Visual | VimMode::VisualLine => {
+                let (start, end, linewise) = self.visual_range(area);
+                Some(if linewise {
+                    (
+                        Pos::new(start.row, 0),
+                        Pos::new(end.row, area.lines()[end.row].len()),
+                    )
+                } else {
+                    (start, end)
+                })
+            }
+            _ => None,
+        };
+        area.set_highlight(highlight);
+    }
+
+    fn set_mode(&mut self, area: &mut TextArea, mode: VimMode) {
+        self.mode = mode;
+        self.column = None;
+        self.update_highlight(area);
+    }
+
+    /// Stores deleted or yanked text in the unnamed register and in
+    /// `register` if one was given; an uppercase name appends to the
+    /// lowercase register.
+    fn set_register(&mut self, register: Option<char>, text: String, linewise: bool) {
+        let value = Register { text, linewise };
+        if let Some(name) = register.filter(|r| *r != '"') {
+            let lower = name.to_ascii_lowercase();
+            let stored = match self.registers.get(&lower) {
+                Some(existing) if name.is_ascii_uppercase() => Register {
+                    text: if value.linewise || existing.linewise {
+                        format!("{}\n{}", existing.text, value.text)
+                    } else {
+                        format!("{}{}", existing.text, value.text)
+                    },
+                    linewise: existing.linewise || value.linewise,
+                },
+                _ => value.clone(),
+            };
+            self.registers.insert(lower, stored);
+        }
+        self.registers.insert('"', value);
+    }
+}
+
+/// Parses the keys of a normal or visual mode command:
+/// `["x][count]command`, where an operator takes `[count]motion` or is doubled.
+fn parse(keys: &str, visual: bool) -> Parsed {
+    let mut chars = keys.chars().peekable();
+
+    let mut register = None;
+    if chars.next_if_eq(&'"').is_some() {
+        match chars.next() {
+            None => return Parsed::Incomplete,
+            Some(c) if c.is_ascii_alphabetic() || c == '"' => register = Some(c),
+            Some(_) => return Parsed::Invalid,
+        }
+    }
+
+    let count = parse_count(&mut chars);
+    let Some(c) = chars.next() else {
+        return Parsed::Incomplete;
+    };
+    let complete = |count, command| Parsed::Complete {
+        register,
+        count,
+        command,
+    };
+
+    let operator = match c {
+        'd' => Some(Operator::Delete),
+        'c' => Some(Operator::Change),
+        'y' => Some(Operator::Yank),
+        _ => None,
+    };
+    if let Some(operator) = operator.filter(|_| !visual) {
+        let motion_count = parse_count(&mut chars);
+        let count = match (count, motion_count) {
+            (Some(a), Some(b)) => Some(a * b),
+            (a, b) => a.or(b),
+        };
+        return match chars.next() {
+            None => Parsed::Incomplete,
+            Some(m) if m == c => complete(count, Command::Operate(operator, None)),
+            Some(m) => match parse_motion(m, &mut chars) {
+                Ok(Some(motion)) => complete(count, Command::Operate(operator, Some(motion))),
+                Ok(None) => Parsed::Invalid,
+                Err(()) => Parsed::Incomplete,
+            },
+        };
+    }
+
+    if c == 'Z' {
+        return match chars.next() {
+            None => Parsed::Incomplete,
+            Some('Z') => complete(count, Command::Save),
+            Some('Q') => complete(count, Command::Discard),
+            Some(_) => Parsed::Invalid,
+        };
+    }
+    match parse_motion(c, &mut chars) {
+        Ok(Some(motion)) => complete(count, Command::Move(motion)),
+        Ok(None) => complete(count, Command::Key(c)),
+        Err(()) => Parsed::Incomplete,
+    }
+}
+
+fn parse_count(chars: &mut Peekable<Chars>) -> Option<usize> {
+    // A leading 0 is the motion to the start of the line, not a count.
+    let mut digits = String::new();
+    while let Some(c) = chars.next_if(|c| c.is_ascii_digit() && (*c != '0' || !digits.is_empty())) {
+        digits.push(c);
+    }
+    digits.parse().ok().map(|n: usize| n.min(10_000))
+}
+
+/// The motion starting with `c`, `Ok(None)` if `c` is not a motion or
+/// `Err(())` if more keys are needed.
+fn parse_motion(c: char, chars: &mut Peekable<Chars>) -> Result<Option<Motion>, ()> {
+    let motion = match c {
+        'h' => Motion::Left,
+        'l' | ' ' => Motion::Right,
+        'k' => Motion::Up,
+        'j' => Motion::Down,
+        'w' => Motion::WordForward,
+        'b' => Motion::WordBackward,
+        'e' => Motion::WordEnd,
+        '0' => Motion::LineStart,
+        '$' => Motion::LineEnd,
+        'G' => Motion::LastLine,
+        'g' => match chars.next() {
+            None => return Err(()),
+            Some('g') => Motion::FirstLine,
+            Some(_) => return Ok(None),
+        },
+        _ => return Ok(None),
+    };
+    Ok(Some(motion))
+}
+
+/// In normal mode the cursor sits on a grapheme, so it can't stay after the
+/// last one in a line.
+fn clamp_cursor(area: &mut TextArea) {
+    let cursor = area.cursor();
+    if cursor.col > 0 && cursor.col == area.lines()[cursor.row].len() {
+        area.move_to(area.grapheme_left(cursor), false);
+    }
+}
+
+fn left_in_line(area: &TextArea, pos: Pos) -> Pos {
+    if pos.col == 0 {
+        pos
+    } else {
+        area.grapheme_left(pos)
+    }
+}
+
+fn right_in_line(area: &TextArea, pos: Pos) -> Pos {
+    if pos.col == area.lines()[pos.row].len() {
+        pos
+    } else {
+        area.grapheme_right(pos)
+    }
+}
+
+fn first_non_blank(area: &TextArea, row: usize) -> Pos {
+    let line = &area.lines()[row];
+    let col = line
+        .char_indices()
+        .find(|(_, c)| !c.is_whitespace())
+        .map_or(line.len(), |(i, _)| i);
+    Pos::new(row, col)
+}
+
+/// The class of the grapheme at `pos`. The end of a line counts as a blank,
+/// and an empty line is a word of its own.
+fn class_at(area: &TextArea, pos: Pos) -> CharClass {
+    let line = &area.lines()[pos.row];
+    if line.is_empty() {
+        return CharClass::EmptyLine;
+    }
+    match line[pos.col..].chars().next() {
+        None => CharClass::Blank,
+        Some(c) if c.is_whitespace() => CharClass::Blank,
+        Some(c) if c.is_alphanumeric() || c == '_' => CharClass::Word,
+        Some(_) => CharClass::Punctuation,
+    }
+}
+
+fn next_word_start(area: &TextArea, pos: Pos) -> Pos {
+    let end = area.end();
+    let class = class_at(area, pos);
+    let mut p = pos;
+    if class != CharClass::Blank && p != end {
+        p = area.grapheme_right(p);
+        if class != CharClass::EmptyLine {
+            while p != end && class_at(area, p) == class {
+                p = area.grapheme_right(p);
+            }
+        }
+    }
+    while p != end && class_at(area, p) == CharClass::Blank {
+        p = area.grapheme_right(p);
+    }
+    p
+}
+
+fn previous_word_start(area: &TextArea, pos: Pos) -> Pos {
+    let mut p = area.grapheme_left(pos);
+    while p != Pos::default() && class_at(area, p) == CharClass::Blank {
+        p = area.grapheme_left(p);
+    }
+    let class = class_at(area, p);
+    if class == CharClass::EmptyLine {
+        return p;
+    }
+    while p.col > 0 {
+        let previous = area.grapheme_left(p);
+        if class_at(area, previous) != class {
+            break;
+        }
+        p = previous;
+    }
+    p
+}
+
+fn word_end(area: &TextArea, pos: Pos) -> Pos {
+    let end = area.end();
+    let mut p = area.grapheme_right(pos);
+    while p != end && matches!(class_at(area, p), CharClass::Blank | CharClass::EmptyLine) {
+        p = area.grapheme_right(p);
+    }
+    let class = class_at(area, p);
+    loop {
+        let next = area.grapheme_right(p);
+        if next == p || class_at(area, next) != class || next.row != p.row {
+            return p;
+        }
+        p = next;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A vim editor on `text` with the cursor at the start.
+    fn editor(text: &str) -> (Vim, TextArea) {
+        let mut area = TextArea::new(text);
+        area.move_to(Pos::default(), false);
+        (Vim::new(), area)
+    }
+
+    /// Types `keys` in normal mode, `\x1b` standing for Esc, and returns the
+    /// outcome of the last key.
+    fn type_keys(vim: &mut Vim, area: &mut TextArea, keys: &str) -> VimOutcome {
+        let mut outcome = VimOutcome::Unchanged;
+        for c in keys.chars() {
+            let code = if c == '\x1b' {
+                KeyCode::Esc
+            } else {
+                KeyCode::Char(c)
+            };
+            outcome = vim.input(area, KeyEvent::new(code, KeyModifiers::NONE));
+            if outcome == VimOutcome::Insert {
+                area.insert_str(c.encode_utf8(&mut [0; 4]));
+            }
+        }
+        outcome
+    }
+
+    #[test]
+    fn moves_by_counted_motions() {
+        let (mut vim, mut area) = editor("one two, three\nfour\n  five six");
+        type_keys(&mut vim, &mut area, "2w");
+        assert_eq!(area.cursor(), Pos::new(0, 7));
+        type_keys(&mut vim, &mut area, "e");
+        assert_eq!(area.cursor(), Pos::new(0, 13));
+        type_keys(&mut vim, &mut area, "3b");
+        assert_eq!(area.cursor(), Pos::new(0, 4));
+        type_keys(&mut vim, &mut area, "$");
+        assert_eq!(area.cursor(), Pos::new(0, 13));
+        type_keys(&mut vim, &mut area, "2j");
+        assert_eq!(area.cursor(), Pos::new(2, 9), "$ sticks to the line end");
+        type_keys(&mut vim, &mut area, "0");
+        assert_eq!(area.cursor(), Pos::new(2, 0));
+        type_keys(&mut vim, &mut area, "gg");
+        assert_eq!(area.cursor(), Pos::new(0, 0));
+        type_keys(&mut vim, &mut area, "3G");
+        assert_eq!(area.cursor(), Pos::new(2, 2));
+    }
+
+    #[test]
+    fn moves_over_whole_graphemes() {
+        let (mut vim, mut area) = editor("ce\u{301}日x");
+        type_keys(&mut vim, &mut area, "l");
+        assert_eq!(area.cursor(), Pos::new(0, 1));
+        type_keys(&mut vim, &mut area, "l");
+        assert_eq!(area.cursor(), Pos::new(0, 4));
+        type_keys(&mut vim, &mut area, "x");
+        assert_eq!(area.text(), "ce\u{301}x");
+        type_keys(&mut vim, &mut area, "$x");
+        assert_eq!(area.text(), "ce\u{301}");
+        assert_eq!(
+            area.cursor(),
+            Pos::new(0, 1),
+            "the cursor stays on a grapheme"
+        );
+    }
+
+    #[test]
+    fn deletes_and_changes_with_operators() {
+        let (mut vim, mut area) = editor("one two three four");
+        assert_eq!(type_keys(&mut vim, &mut area, "2dw"), VimOutcome::Changed);
+        assert_eq!(area.text(), "three four");
+        type_keys(&mut vim, &mut area, "cwsix\x1b");
+        assert_eq!(area.text(), "six four");
+        assert_eq!(vim.mode(), VimMode::Normal);
+        type_keys(&mut vim, &mut area, "wD");
+        assert_eq!(area.text(), "six ");
+
+        let (mut vim, mut area) = editor("a\nb\nc\nd");
+        type_keys(&mut vim, &mut area, "j2dd");
+        assert_eq!(area.text(), "a\nd");
+        type_keys(&mut vim, &mut area, "Gdd");
+        assert_eq!(area.text(), "a");
+    }
+
+    #[test]
+    fn yanks_and_puts_through_registers() {
+        let (mut vim, mut area) = editor("first\nsecond");
+        type_keys(&mut vim, &mut area, "\"ayyj\"byy");
+        type_keys(&mut vim, &mut area, "\"ap");
+        assert_eq!(area.text(), "first\nsecond\nfirst");
+        type_keys(&mut vim, &mut area, "gg\"Ayy\"aP");
+        assert_eq!(area.text(), "first\nfirst\nfirst\nsecond\nfirst");
+
+        let (mut vim, mut area) = editor("ab");
+        type_keys(&mut vim, &mut area, "yl3p");
+        assert_eq!(area.text(), "aaaab");
+        assert_eq!(
+            type_keys(&mut vim, &mut area, "\"zp"),
+            VimOutcome::Unchanged
+        );
+        assert_eq!(vim.status(), "Nothing in register z");
+    }
+
+    #[test]
+    fn operates_on_visual_selections() {
+        let (mut vim, mut area) = editor("hello world\nagain");
+        type_keys(&mut vim, &mut area, "wvld");
+        assert_eq!(area.text(), "hello rld\nagain");
+        type_keys(&mut vim, &mut area, "Vjy");
+        assert_eq!(vim.mode(), VimMode::Normal);
+        type_keys(&mut vim, &mut area, "Gp");
+        assert_eq!(area.text(), "hello rld\nagain\nhello rld\nagain");
+    }
+
+    #[test]
+    fn reports_editor_commands() {
+        let (mut vim, mut area) = editor("text");
+        assert_eq!(type_keys(&mut vim, &mut area, "ZZ"), VimOutcome::Save);
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+        type_keys(&mut vim, &mut area, ":wq");
+        assert_eq!(vim.input(&mut area, enter), VimOutcome::Save);
+        type_keys(&mut vim, &mut area, ":nope");
+        assert_eq!(vim.input(&mut area, enter), VimOutcome::Unchanged);
+        assert_eq!(vim.status(), "Not an editor command: nope");
+        assert_eq!(type_keys(&mut vim, &mut area, "u"), VimOutcome::Undo);
+    }
+}